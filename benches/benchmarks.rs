@@ -10,6 +10,24 @@ fn bench_detail_new(c: &mut Criterion) {
     });
 }
 
+fn bench_detail_new_density(c: &mut Criterion) {
+    let x = [
+        0.778_240, 0.020_000, 0.060_000, 0.080_000, 0.030_000, 0.001_500, 0.003_000, 0.000_500,
+        0.001_650, 0.002_150, 0.000_880, 0.000_240, 0.000_150, 0.000_090, 0.004_000, 0.005_000,
+        0.002_000, 0.000_100, 0.002_500, 0.007_000, 0.001_000,
+    ];
+
+    c.bench_function("Detail_new_density", |b| {
+        b.iter(|| {
+            let mut aga8_test: Detail = Detail::new();
+            aga8_test.x = x;
+            aga8_test.p = 50_000.0;
+            aga8_test.t = 400.0;
+            aga8_test.density().unwrap();
+        })
+    });
+}
+
 fn bench_detail_density(c: &mut Criterion) {
     let mut aga8_test: Detail = Detail::new();
     aga8_test.x = [
@@ -45,6 +63,46 @@ fn bench_detail_properties(c: &mut Criterion) {
     });
 }
 
+fn bench_detail_compressibility(c: &mut Criterion) {
+    let mut aga8_test: Detail = Detail::new();
+    aga8_test.x = [
+        0.778_240, 0.020_000, 0.060_000, 0.080_000, 0.030_000, 0.001_500, 0.003_000, 0.000_500,
+        0.001_650, 0.002_150, 0.000_880, 0.000_240, 0.000_150, 0.000_090, 0.004_000, 0.005_000,
+        0.002_000, 0.000_100, 0.002_500, 0.007_000, 0.001_000,
+    ];
+    aga8_test.p = 50_000.0;
+    aga8_test.t = 400.0;
+
+    c.bench_function("Detail_compressibility", |b| {
+        b.iter(|| {
+            aga8_test.compressibility().unwrap();
+        })
+    });
+    c.bench_function("Detail_density_properties", |b| {
+        b.iter(|| {
+            aga8_test.density().unwrap();
+            aga8_test.properties();
+        })
+    });
+}
+
+fn bench_detail_pressure(c: &mut Criterion) {
+    let mut aga8_test: Detail = Detail::new();
+    aga8_test.x = [
+        0.778_240, 0.020_000, 0.060_000, 0.080_000, 0.030_000, 0.001_500, 0.003_000, 0.000_500,
+        0.001_650, 0.002_150, 0.000_880, 0.000_240, 0.000_150, 0.000_090, 0.004_000, 0.005_000,
+        0.002_000, 0.000_100, 0.002_500, 0.007_000, 0.001_000,
+    ];
+    aga8_test.t = 400.0;
+    aga8_test.d = 12.807_924_036_488_01;
+
+    c.bench_function("Detail_pressure", |b| {
+        b.iter(|| {
+            aga8_test.pressure();
+        })
+    });
+}
+
 fn bench_gerg_new(c: &mut Criterion) {
     c.bench_function("Gerg_new", |b| {
         b.iter(|| {
@@ -91,13 +149,38 @@ fn bench_gerg_properties(c: &mut Criterion) {
     });
 }
 
+fn bench_gerg_composition_sweep(c: &mut Criterion) {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.x = [
+        0.0, 0.77824, 0.02, 0.06, 0.08, 0.03, 0.0015, 0.003, 0.0005, 0.00165, 0.00215, 0.00088,
+        0.00024, 0.00015, 0.00009, 0.004, 0.005, 0.002, 0.0001, 0.0025, 0.007, 0.001,
+    ];
+    gerg_test.t = 400.0;
+    gerg_test.p = 50000.0;
+
+    c.bench_function("Gerg_composition_sweep", |b| {
+        b.iter(|| {
+            for i in 0..20 {
+                gerg_test.x[1] = 0.77824 - 0.002 * i as f64;
+                gerg_test.x[4] = 0.08 + 0.002 * i as f64;
+                gerg_test.d = 0.0;
+                gerg_test.density(0).unwrap();
+            }
+        })
+    });
+}
+
 criterion_group!(
     benches,
     bench_detail_new,
+    bench_detail_new_density,
     bench_detail_density,
     bench_detail_properties,
+    bench_detail_compressibility,
+    bench_detail_pressure,
     bench_gerg_new,
     bench_gerg_density,
     bench_gerg_properties,
+    bench_gerg_composition_sweep,
 );
 criterion_main!(benches);
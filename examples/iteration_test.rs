@@ -4,30 +4,6 @@ use rand::prelude::*;
 use std::fs::OpenOptions;
 use std::io::{prelude::*, BufWriter};
 
-const COMP_FULL: Composition = Composition {
-    methane: 0.778_24,
-    nitrogen: 0.02,
-    carbon_dioxide: 0.06,
-    ethane: 0.08,
-    propane: 0.03,
-    isobutane: 0.001_5,
-    n_butane: 0.003,
-    isopentane: 0.000_5,
-    n_pentane: 0.001_65,
-    hexane: 0.002_15,
-    heptane: 0.000_88,
-    octane: 0.000_24,
-    nonane: 0.000_15,
-    decane: 0.000_09,
-    hydrogen: 0.004,
-    oxygen: 0.005,
-    carbon_monoxide: 0.002,
-    water: 0.000_1,
-    hydrogen_sulfide: 0.002_5,
-    helium: 0.007,
-    argon: 0.001,
-};
-
 const COMP_PARTIAL: Composition = Composition {
     methane: 0.965,
     nitrogen: 0.003,
@@ -1,5 +1,60 @@
 //! Gas composition
 
+use std::collections::HashMap;
+
+// Number of components in a composition
+pub(crate) const NC: usize = 21;
+
+// Molar masses (g/mol), in the order of `Composition::to_array()`
+pub(crate) const MMI: [f64; NC] = [
+    16.043,  // Methane
+    28.0135, // Nitrogen
+    44.01,   // Carbon dioxide
+    30.07,   // Ethane
+    44.097,  // Propane
+    58.123,  // Isobutane
+    58.123,  // n-Butane
+    72.15,   // Isopentane
+    72.15,   // n-Pentane
+    86.177,  // Hexane
+    100.204, // Heptane
+    114.231, // Octane
+    128.258, // Nonane
+    142.285, // Decane
+    2.0159,  // Hydrogen
+    31.9988, // Oxygen
+    28.01,   // Carbon monoxide
+    18.0153, // Water
+    34.082,  // Hydrogen sulfide
+    4.0026,  // Helium
+    39.948,  // Argon
+];
+
+// The field names of `Composition`, in the order of `Composition::to_array()`.
+const NAMES: [&str; NC] = [
+    "methane",
+    "nitrogen",
+    "carbon_dioxide",
+    "ethane",
+    "propane",
+    "isobutane",
+    "n_butane",
+    "isopentane",
+    "n_pentane",
+    "hexane",
+    "heptane",
+    "octane",
+    "nonane",
+    "decane",
+    "hydrogen",
+    "oxygen",
+    "carbon_monoxide",
+    "water",
+    "hydrogen_sulfide",
+    "helium",
+    "argon",
+];
+
 /// A complete gas composition made up of gas components.
 ///
 /// A gas composition contains 21 gas components named by the field names in the struct.
@@ -20,7 +75,8 @@
 /// assert!((air.sum() - 1.0).abs() < 1.0e-10);
 /// ```
 #[repr(C)]
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Composition {
     /// Methane CH<sub>4</sub>
     pub methane: f64,
@@ -66,7 +122,129 @@ pub struct Composition {
     pub argon: f64,
 }
 
+/// The gas components of a [`Composition`].
+///
+/// The discriminant of each component is its index in [`Composition::to_array()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    /// Methane CH<sub>4</sub>
+    Methane,
+    /// Nitrogen N
+    Nitrogen,
+    /// Carbon Dioxide CO<sub>2</sub>
+    CarbonDioxide,
+    /// Ethane C<sub>2</sub>H<sub>6</sub>
+    Ethane,
+    /// Propane C<sub>3</sub>H<sub>8</sub>
+    Propane,
+    /// Isobutane C<sub>4</sub>H<sub>10</sub>
+    Isobutane,
+    /// Butane C<sub>4</sub>H<sub>10</sub>
+    NButane,
+    /// Isopentane C<sub>5</sub>H<sub>12</sub>
+    Isopentane,
+    /// Pentane C<sub>5</sub>H<sub>12</sub>
+    NPentane,
+    /// Hexane C<sub>6</sub>H<sub>14</sub>
+    Hexane,
+    /// Heptane C<sub>7</sub>H<sub>16</sub>
+    Heptane,
+    /// Octane C<sub>8</sub>H<sub>18</sub>
+    Octane,
+    /// Nonane C<sub>9</sub>H<sub>20</sub>
+    Nonane,
+    /// Decane C<sub>10</sub>H<sub>22</sub>
+    Decane,
+    /// Hydrogen H
+    Hydrogen,
+    /// Oxygen O
+    Oxygen,
+    /// Carbon monoxide CO
+    CarbonMonoxide,
+    /// Water H<sub>2</sub>O
+    Water,
+    /// Hydrogen sulfide H<sub>2</sub>S
+    HydrogenSulfide,
+    /// Helium He
+    Helium,
+    /// Argon Ar
+    Argon,
+}
+
+impl Component {
+    /// All components, in the order of [`Composition::to_array()`].
+    pub const ALL: [Component; NC] = [
+        Component::Methane,
+        Component::Nitrogen,
+        Component::CarbonDioxide,
+        Component::Ethane,
+        Component::Propane,
+        Component::Isobutane,
+        Component::NButane,
+        Component::Isopentane,
+        Component::NPentane,
+        Component::Hexane,
+        Component::Heptane,
+        Component::Octane,
+        Component::Nonane,
+        Component::Decane,
+        Component::Hydrogen,
+        Component::Oxygen,
+        Component::CarbonMonoxide,
+        Component::Water,
+        Component::HydrogenSulfide,
+        Component::Helium,
+        Component::Argon,
+    ];
+}
+
+/// Rules for lumping components that are not part of AGA8 into a surrogate component.
+///
+/// See [`Composition::with_lumping()`].
+///
+/// # Example
+/// ```
+/// use aga8::composition::{Component, LumpingRules};
+///
+/// let mut rules = LumpingRules::default();
+/// rules.surrogates.insert("neopentane".to_string(), Component::NPentane);
+/// rules.surrogates.insert("benzene".to_string(), Component::Hexane);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LumpingRules {
+    /// The surrogate component for each unsupported component name.
+    /// The names are matched without regard to ASCII case.
+    pub surrogates: HashMap<String, Component>,
+}
+
 impl Composition {
+    /// Standard dry air in mole fractions.
+    ///
+    /// N<sub>2</sub> 0.78102, O<sub>2</sub> 0.20946, Ar 0.00916 and CO<sub>2</sub> 0.00036.
+    pub const DRY_AIR: Composition = Composition {
+        methane: 0.0,
+        nitrogen: 0.781_02,
+        carbon_dioxide: 0.000_36,
+        ethane: 0.0,
+        propane: 0.0,
+        isobutane: 0.0,
+        n_butane: 0.0,
+        isopentane: 0.0,
+        n_pentane: 0.0,
+        hexane: 0.0,
+        heptane: 0.0,
+        octane: 0.0,
+        nonane: 0.0,
+        decane: 0.0,
+        hydrogen: 0.0,
+        oxygen: 0.209_46,
+        carbon_monoxide: 0.0,
+        water: 0.0,
+        hydrogen_sulfide: 0.0,
+        helium: 0.0,
+        argon: 0.009_16,
+    };
+
     /// Compute the sum of all components.
     ///
     /// # Example
@@ -151,6 +329,262 @@ impl Composition {
         Ok(())
     }
 
+    /// Normalizes the composition sum to 1.0, and returns the sum before normalization.
+    ///
+    /// The original sum can be used to detect suspicious data, like a gas
+    /// chromatograph analysis that sums to 0.85.
+    ///
+    /// # Example
+    /// ```
+    /// let mut comp = aga8::composition::Composition {
+    ///     methane: 0.80,
+    ///     ethane: 0.05,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let sum = comp.normalize_reporting().unwrap();
+    ///
+    /// assert!((sum - 0.85).abs() < 1.0e-10);
+    /// assert!((comp.sum() - 1.0).abs() < 1.0e-10);
+    /// ```
+    pub fn normalize_reporting(&mut self) -> Result<f64, CompositionError> {
+        let sum = self.sum();
+        self.normalize()?;
+        Ok(sum)
+    }
+
+    /// Returns the components as an array of mole fractions.
+    ///
+    /// The components are in the same order as the fields of the struct,
+    /// which is also the order of `x` in [`Detail`](crate::detail::Detail).
+    pub fn to_array(&self) -> [f64; NC] {
+        [
+            self.methane,
+            self.nitrogen,
+            self.carbon_dioxide,
+            self.ethane,
+            self.propane,
+            self.isobutane,
+            self.n_butane,
+            self.isopentane,
+            self.n_pentane,
+            self.hexane,
+            self.heptane,
+            self.octane,
+            self.nonane,
+            self.decane,
+            self.hydrogen,
+            self.oxygen,
+            self.carbon_monoxide,
+            self.water,
+            self.hydrogen_sulfide,
+            self.helium,
+            self.argon,
+        ]
+    }
+
+    /// Creates a composition from an array of mole fractions.
+    ///
+    /// The components must be in the same order as the fields of the struct.
+    pub fn from_array(x: &[f64; NC]) -> Self {
+        Composition {
+            methane: x[0],
+            nitrogen: x[1],
+            carbon_dioxide: x[2],
+            ethane: x[3],
+            propane: x[4],
+            isobutane: x[5],
+            n_butane: x[6],
+            isopentane: x[7],
+            n_pentane: x[8],
+            hexane: x[9],
+            heptane: x[10],
+            octane: x[11],
+            nonane: x[12],
+            decane: x[13],
+            hydrogen: x[14],
+            oxygen: x[15],
+            carbon_monoxide: x[16],
+            water: x[17],
+            hydrogen_sulfide: x[18],
+            helium: x[19],
+            argon: x[20],
+        }
+    }
+
+    /// Creates a composition from an array of mass fractions.
+    ///
+    /// The mole fractions are x<sub>i</sub> = (w<sub>i</sub>/M<sub>i</sub>)/Σ(w<sub>j</sub>/M<sub>j</sub>),
+    /// with the molar masses of [`molar_mass()`](Composition::molar_mass).
+    /// The mass fractions do not need to be normalized. If they are all zero,
+    /// the composition is empty.
+    ///
+    /// The components must be in the same order as the fields of the struct.
+    pub fn from_mass_fractions(w: &[f64; NC]) -> Self {
+        let mut x = [0.0; NC];
+        for ((xi, wi), mm) in x.iter_mut().zip(w).zip(MMI.iter()) {
+            *xi = wi / mm;
+        }
+        let sum: f64 = x.iter().sum();
+        if sum > 0.0 {
+            x.iter_mut().for_each(|xi| *xi /= sum);
+        }
+        Composition::from_array(&x)
+    }
+
+    /// Creates a normalized composition from a gas analysis with named components.
+    ///
+    /// Components with the same name as a field of the struct, e.g. `"n_butane"`, are used
+    /// directly. Other components are added to the surrogate component given by `rules`,
+    /// e.g. neopentane to n-pentane. The names are matched without regard to ASCII case.
+    /// The result is normalized, so the amounts can be in any unit, e.g. mole percent.
+    ///
+    /// **Note:** Lumping is an approximation. The mole fraction of the surrogate is increased
+    /// by the mole fraction of the lumped component, so the molar mass and the properties of
+    /// the gas change, e.g. benzene is lighter than hexane.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use aga8::composition::{Component, Composition, LumpingRules};
+    ///
+    /// let mut rules = LumpingRules::default();
+    /// rules.surrogates.insert("neopentane".to_string(), Component::NPentane);
+    /// let raw = HashMap::from([
+    ///     ("methane".to_string(), 99.0),
+    ///     ("neopentane".to_string(), 1.0),
+    /// ]);
+    ///
+    /// let comp = Composition::with_lumping(&raw, &rules).unwrap();
+    ///
+    /// assert!((comp.n_pentane - 0.01).abs() < 1.0e-12);
+    /// ```
+    ///
+    /// ## Error
+    /// Returns [`CompositionError::UnknownComponent`] if a name is neither a field of the
+    /// struct nor in `rules`, [`CompositionError::NotFinite`] if an amount is NaN or infinite,
+    /// or the error of [`check()`](Composition::check) for the normalized composition,
+    /// e.g. [`CompositionError::Negative`] for a negative amount.
+    pub fn with_lumping(
+        raw: &HashMap<String, f64>,
+        rules: &LumpingRules,
+    ) -> Result<Self, CompositionError> {
+        let mut x = [0.0; NC];
+        for (name, amount) in raw {
+            let i = match NAMES.iter().position(|n| n.eq_ignore_ascii_case(name)) {
+                Some(i) => i,
+                None => rules
+                    .surrogates
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                    .map(|(_, c)| *c as usize)
+                    .ok_or(CompositionError::UnknownComponent)?,
+            };
+            if !amount.is_finite() {
+                return Err(CompositionError::NotFinite);
+            }
+            x[i] += amount;
+        }
+
+        let mut comp = Composition::from_array(&x);
+        comp.normalize()?;
+        comp.check()?;
+        Ok(comp)
+    }
+
+    /// Calculates the molar mass of the composition in g/mol.
+    ///
+    /// The molar masses of the components are the ones used by the DETAIL equation of state.
+    /// The composition is not normalized before the calculation.
+    ///
+    /// # Example
+    /// ```
+    /// let comp = aga8::composition::Composition {
+    ///     methane: 1.0,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!((comp.molar_mass() - 16.043).abs() < 1.0e-10);
+    /// ```
+    pub fn molar_mass(&self) -> f64 {
+        self.to_array()
+            .iter()
+            .zip(MMI.iter())
+            .map(|(x, mm)| x * mm)
+            .sum()
+    }
+
+    /// Calculates the distance between this composition and a reference composition.
+    ///
+    /// The distance is the Euclidean (L2) norm of the mole fraction differences,
+    /// √(Σ (x<sub>i</sub> - x<sub>ref,i</sub>)<sup>2</sup>).
+    /// The compositions are not normalized before the calculation.
+    ///
+    /// # Example
+    /// ```
+    /// use aga8::composition::Composition;
+    ///
+    /// let comp = Composition {
+    ///     methane: 0.9,
+    ///     ethane: 0.1,
+    ///     ..Default::default()
+    /// };
+    /// let reference = Composition {
+    ///     methane: 1.0,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!((comp.distance(&reference) - 0.02_f64.sqrt()).abs() < 1.0e-12);
+    /// ```
+    pub fn distance(&self, reference: &Composition) -> f64 {
+        self.to_array()
+            .iter()
+            .zip(reference.to_array().iter())
+            .map(|(x, x_ref)| (x - x_ref).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Creates a two-component composition with the given molar mass.
+    ///
+    /// The mole fraction x of component `a` is solved from the linear mixing rule
+    /// x·M<sub>a</sub> + (1 - x)·M<sub>b</sub> = M, with the molar masses of [`molar_mass()`](Composition::molar_mass).
+    ///
+    /// # Example
+    /// ```
+    /// use aga8::composition::{Component, Composition};
+    ///
+    /// let comp =
+    ///     Composition::binary_from_molar_mass(Component::Methane, Component::Nitrogen, 20.0)
+    ///         .unwrap();
+    ///
+    /// assert!((comp.molar_mass() - 20.0).abs() < 1.0e-10);
+    /// ```
+    ///
+    /// ## Error
+    /// Returns [`CompositionError::OutOfRange`] if the target molar mass is not
+    /// between the molar masses of the two components.
+    pub fn binary_from_molar_mass(
+        a: Component,
+        b: Component,
+        target_mm: f64,
+    ) -> Result<Composition, CompositionError> {
+        let (mm_a, mm_b) = (MMI[a as usize], MMI[b as usize]);
+        if target_mm < mm_a.min(mm_b) || target_mm > mm_a.max(mm_b) {
+            return Err(CompositionError::OutOfRange);
+        }
+
+        let x_a = if mm_a == mm_b {
+            1.0
+        } else {
+            (target_mm - mm_b) / (mm_a - mm_b)
+        };
+        let mut x = [0.0; NC];
+        x[b as usize] = 1.0 - x_a;
+        x[a as usize] = x_a;
+        Ok(Composition::from_array(&x))
+    }
+
     /// Checks that the composition is valid.
     ///
     /// # Example
@@ -163,7 +597,15 @@ impl Composition {
     ///
     /// assert_eq!(comp.check(), Ok(()));
     /// ```
+    ///
+    /// ## Error
+    /// Returns [`CompositionError::Negative`] if a component is negative,
+    /// [`CompositionError::Empty`] if all components are zero, or
+    /// [`CompositionError::BadSum`] if the sum is not within 0.01 of 1.0.
     pub fn check(&self) -> Result<(), CompositionError> {
+        if self.to_array().iter().any(|x| *x < 0.0) {
+            return Err(CompositionError::Negative);
+        }
         if (self.sum() - 0.0).abs() < 1.0e-10 {
             return Err(CompositionError::Empty);
         }
@@ -172,6 +614,85 @@ impl Composition {
         }
         Ok(())
     }
+
+    /// Classifies the composition by the gas ranges of AGA Report No. 8, Part 1, Table 1.
+    ///
+    /// The butanes, the pentanes and hexane and heavier are each checked as a group.
+    /// The expanded range limits of hexanes+ and water are the dew point, which is not checked.
+    ///
+    /// | Component | Normal range (mol %) | Expanded range (mol %) |
+    /// |-----------|----------------------|------------------------|
+    /// | Methane | 45 - 100 | 0 - 100 |
+    /// | Nitrogen | 0 - 50 | 0 - 100 |
+    /// | Carbon dioxide | 0 - 30 | 0 - 100 |
+    /// | Ethane | 0 - 10 | 0 - 100 |
+    /// | Propane | 0 - 3.5 | 0 - 12 |
+    /// | Butanes | 0 - 1.5 | 0 - 6 |
+    /// | Pentanes | 0 - 0.5 | 0 - 4 |
+    /// | Hexanes+ | 0 - 0.1 | dew point |
+    /// | Helium | 0 - 0.2 | 0 - 3 |
+    /// | Hydrogen | 0 - 10 | 0 - 100 |
+    /// | Carbon monoxide | 0 - 3 | 0 - 3 |
+    /// | Argon | - | 0 - 1 |
+    /// | Oxygen | - | 0 - 21 |
+    /// | Water | 0 - 0.05 | dew point |
+    /// | Hydrogen sulfide | 0 - 0.02 | 0 - 100 |
+    ///
+    /// # Example
+    /// ```
+    /// use aga8::composition::{Composition, CompositionRange};
+    ///
+    /// let comp = Composition {
+    ///     methane: 0.9,
+    ///     propane: 0.1,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(comp.range(), CompositionRange::Expanded);
+    /// ```
+    pub fn range(&self) -> CompositionRange {
+        let sum = self.sum();
+        let x = |v: f64| v / sum;
+        let groups = [
+            (x(self.nitrogen), 0.5, 1.0),
+            (x(self.carbon_dioxide), 0.3, 1.0),
+            (x(self.ethane), 0.1, 1.0),
+            (x(self.propane), 0.035, 0.12),
+            (x(self.isobutane + self.n_butane), 0.015, 0.06),
+            (x(self.isopentane + self.n_pentane), 0.005, 0.04),
+            (
+                x(self.hexane + self.heptane + self.octane + self.nonane + self.decane),
+                0.001,
+                1.0,
+            ),
+            (x(self.helium), 0.002, 0.03),
+            (x(self.hydrogen), 0.1, 1.0),
+            (x(self.carbon_monoxide), 0.03, 0.03),
+            (x(self.argon), 0.0, 0.01),
+            (x(self.oxygen), 0.0, 0.21),
+            (x(self.water), 0.000_5, 1.0),
+            (x(self.hydrogen_sulfide), 0.000_2, 1.0),
+        ];
+
+        if groups.iter().any(|(xi, _, expanded)| xi > expanded) {
+            CompositionRange::OutOfRange
+        } else if x(self.methane) < 0.45 || groups.iter().any(|(xi, normal, _)| xi > normal) {
+            CompositionRange::Expanded
+        } else {
+            CompositionRange::Normal
+        }
+    }
+}
+
+/// Gas ranges of AGA Report No. 8, Part 1, see [`Composition::range()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompositionRange {
+    /// Within the normal range, where the stated uncertainty of the DETAIL equation applies
+    Normal,
+    /// Within the expanded range, with a higher uncertainty
+    Expanded,
+    /// Outside the expanded range
+    OutOfRange,
 }
 
 /// Error conditions for composition
@@ -184,6 +705,14 @@ pub enum CompositionError {
     Empty,
     /// The sum of the components is not 1.0000
     BadSum,
+    /// A requested property is outside the range that the components can give
+    OutOfRange,
+    /// A component name is not known
+    UnknownComponent,
+    /// A component is negative
+    Negative,
+    /// A component is not a finite number
+    NotFinite,
 }
 
 #[cfg(test)]
@@ -222,6 +751,18 @@ mod tests {
         assert_eq!(comp.check(), Err(CompositionError::BadSum));
     }
 
+    #[test]
+    fn negative_is_error() {
+        let comp = Composition {
+            methane: 0.9,
+            ethane: 0.13,
+            propane: -0.03,
+            ..Default::default()
+        };
+
+        assert_eq!(comp.check(), Err(CompositionError::Negative));
+    }
+
     #[test]
     fn normalized_is_1() {
         let mut comp = Composition {
@@ -235,6 +776,108 @@ mod tests {
         assert_eq!(comp.sum(), 1.0);
     }
 
+    #[test]
+    fn array_round_trip() {
+        let comp = Composition {
+            methane: 0.9,
+            ethane: 0.06,
+            argon: 0.04,
+            ..Default::default()
+        };
+
+        let x = comp.to_array();
+
+        assert_eq!(x[0], 0.9);
+        assert_eq!(x[3], 0.06);
+        assert_eq!(x[20], 0.04);
+        assert_eq!(Composition::from_array(&x), comp);
+    }
+
+    #[test]
+    fn binary_from_molar_mass() {
+        let comp =
+            Composition::binary_from_molar_mass(Component::Methane, Component::Nitrogen, 16.043)
+                .unwrap();
+
+        assert_eq!(comp.methane, 1.0);
+        assert_eq!(comp.nitrogen, 0.0);
+        assert_eq!(
+            Composition::binary_from_molar_mass(Component::Methane, Component::Nitrogen, 30.0),
+            Err(CompositionError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn distance() {
+        let comp = Composition {
+            methane: 0.9,
+            ethane: 0.06,
+            argon: 0.04,
+            ..Default::default()
+        };
+        let swapped = Composition {
+            ethane: 0.04,
+            argon: 0.06,
+            ..comp
+        };
+
+        assert_eq!(comp.distance(&comp), 0.0);
+        assert!((comp.distance(&swapped) - 0.02 * 2.0_f64.sqrt()).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn lumping() {
+        let mut rules = LumpingRules::default();
+        rules
+            .surrogates
+            .insert("Neopentane".to_string(), Component::NPentane);
+        let raw = HashMap::from([
+            ("methane".to_string(), 90.0),
+            ("n_pentane".to_string(), 2.0),
+            ("neopentane".to_string(), 3.0),
+            ("ethane".to_string(), 5.0),
+        ]);
+
+        let comp = Composition::with_lumping(&raw, &rules).unwrap();
+
+        assert!((comp.n_pentane - 0.05).abs() < 1.0e-12);
+        assert!((comp.methane - 0.9).abs() < 1.0e-12);
+        assert!((comp.sum() - 1.0).abs() < 1.0e-12);
+
+        let raw = HashMap::from([("benzene".to_string(), 1.0)]);
+        assert_eq!(
+            Composition::with_lumping(&raw, &rules),
+            Err(CompositionError::UnknownComponent)
+        );
+
+        let raw = HashMap::from([
+            ("methane".to_string(), 99.0),
+            ("ethane".to_string(), f64::NAN),
+        ]);
+        assert_eq!(
+            Composition::with_lumping(&raw, &rules),
+            Err(CompositionError::NotFinite)
+        );
+
+        let raw = HashMap::from([("methane".to_string(), 101.0), ("ethane".to_string(), -1.0)]);
+        assert_eq!(
+            Composition::with_lumping(&raw, &rules),
+            Err(CompositionError::Negative)
+        );
+    }
+
+    #[test]
+    fn normalize_reporting_returns_sum() {
+        let mut comp = Composition {
+            methane: 1.5,
+            nitrogen: 0.5,
+            ..Default::default()
+        };
+
+        assert_eq!(comp.normalize_reporting(), Ok(2.0));
+        assert!((comp.sum() - 1.0).abs() < 1.0e-10);
+    }
+
     #[test]
     fn normalize_empty_is_error() {
         let mut comp = Composition {
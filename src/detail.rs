@@ -1,39 +1,16 @@
 //! The AGA8 DETAIL equation of state.
 
-use crate::composition::{Composition, CompositionError};
-use crate::DensityError;
+use crate::composition::{Component, Composition, CompositionError, CompositionRange, MMI, NC};
+use crate::heating_value;
+use crate::{DensityError, PropertyError, PropertySet, SpecificProperties};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
 
-pub(crate) const NC: usize = 21;
 const MAXFLDS: usize = 21;
 const NTERMS: usize = 58;
 const EPSILON: f64 = 1e-15;
 const RDETAIL: f64 = 8.31451;
 
-// Molar masses (g/mol)
-const MMI: [f64; 21] = [
-    16.043,  // Methane
-    28.0135, // Nitrogen
-    44.01,   // Carbon dioxide
-    30.07,   // Ethane
-    44.097,  // Propane
-    58.123,  // Isobutane
-    58.123,  // n-Butane
-    72.15,   // Isopentane
-    72.15,   // n-Pentane
-    86.177,  // Hexane
-    100.204, // Heptane
-    114.231, // Octane
-    128.258, // Nonane
-    142.285, // Decane
-    2.0159,  // Hydrogen
-    31.9988, // Oxygen
-    28.01,   // Carbon monoxide
-    18.0153, // Water
-    34.082,  // Hydrogen sulfide
-    4.0026,  // Helium
-    39.948,  // Argon
-];
-
 // Coefficients of the equation of state
 const AN: [f64; NTERMS] = [
     0.153_832_6,
@@ -606,6 +583,125 @@ const TH0I: [[f64; 7]; MAXFLDS] = [
     [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
 ];
 
+/// Binary interaction parameters for a pair of components.
+///
+/// The parameters are named as in AGA Report No. 8, Part 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinaryParams {
+    /// Binary energy parameter E<sub>ij</sub>
+    pub eij: f64,
+    /// Binary conformal energy parameter U<sub>ij</sub>
+    pub uij: f64,
+    /// Binary size parameter K<sub>ij</sub>
+    pub kij: f64,
+    /// Binary orientation parameter G<sub>ij</sub>
+    pub gij: f64,
+}
+
+/// Selects one of the binary interaction parameters in [`BinaryParams`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryParamKind {
+    /// Binary energy parameter E<sub>ij</sub>
+    Eij,
+    /// Binary conformal energy parameter U<sub>ij</sub>
+    Uij,
+    /// Binary size parameter K<sub>ij</sub>
+    Kij,
+    /// Binary orientation parameter G<sub>ij</sub>
+    Gij,
+}
+
+/// Flags for the outputs of [`Detail`] that are up to date.
+///
+/// The flags are cleared when `t`, `p`, `d` or `x` are changed.
+/// See [`computed_fields()`](Detail::computed_fields).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ComputedFields(u8);
+
+impl ComputedFields {
+    /// `d` and `z` were calculated by [`density()`](Detail::density)
+    pub const DENSITY: ComputedFields = ComputedFields(1);
+    /// The property fields were calculated by [`properties()`](Detail::properties)
+    pub const PROPERTIES: ComputedFields = ComputedFields(2);
+    /// `mm` was calculated by [`molar_mass()`](Detail::molar_mass)
+    pub const MOLAR_MASS: ComputedFields = ComputedFields(4);
+
+    /// Returns true if all the flags in `other` are set.
+    pub fn contains(self, other: ComputedFields) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if no flags are set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for ComputedFields {
+    type Output = ComputedFields;
+
+    fn bitor(self, rhs: ComputedFields) -> ComputedFields {
+        ComputedFields(self.0 | rhs.0)
+    }
+}
+
+/// The density and compressibility at one metering condition.
+///
+/// See [`metering_summary()`](Detail::metering_summary).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MeteringState {
+    /// Temperature in K
+    pub t: f64,
+    /// Pressure in kPa
+    pub p: f64,
+    /// Molar concentration in mol/l
+    pub d: f64,
+    /// Compressibility factor
+    pub z: f64,
+    /// Supercompressibility factor F<sub>pv</sub> = √(Z<sub>standard</sub>/Z)
+    pub fpv: f64,
+}
+
+/// The state of a gas at line, standard and normal conditions.
+///
+/// See [`metering_summary()`](Detail::metering_summary).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MeteringSummary {
+    /// Line conditions
+    pub line: MeteringState,
+    /// Standard conditions, 15 °C and 101.325 kPa
+    pub standard: MeteringState,
+    /// Normal conditions, 0 °C and 101.325 kPa
+    pub normal: MeteringState,
+    /// Real gas relative density, the density of the gas divided by the density of dry air
+    /// at standard conditions
+    pub relative_density: f64,
+}
+
+/// Partial derivatives of the density, compressibility factor, enthalpy and speed of sound
+/// with respect to temperature and pressure.
+///
+/// See [`output_jacobian()`](Detail::output_jacobian).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OutputJacobian {
+    /// ∂d/∂T at constant P in mol/(l-K)
+    pub dd_dt: f64,
+    /// ∂d/∂P at constant T in mol/(l-kPa)
+    pub dd_dp: f64,
+    /// ∂Z/∂T at constant P in 1/K
+    pub dz_dt: f64,
+    /// ∂Z/∂P at constant T in 1/kPa
+    pub dz_dp: f64,
+    /// ∂h/∂T at constant P in J/(mol-K)
+    pub dh_dt: f64,
+    /// ∂h/∂P at constant T in J/(mol-kPa)
+    pub dh_dp: f64,
+    /// ∂w/∂T at constant P in m/(s-K)
+    pub dw_dt: f64,
+    /// ∂w/∂P at constant T in m/(s-kPa)
+    pub dw_dp: f64,
+}
+
 /// Implements the DETAIL equation of state described in
 /// AGA Report No. 8, Part 1, Third Edition, April 2017.
 ///
@@ -653,7 +749,6 @@ const TH0I: [[f64; 7]; MAXFLDS] = [
 ///
 /// assert!((12.807_924_036_488_01 - aga8_test.d).abs() < 1.0e-10);
 /// ```
-
 pub struct Detail {
     // Calculated in the Pressure subroutine,
     // but not included as an argument since it
@@ -702,7 +797,13 @@ pub struct Detail {
     pub kappa: f64,
     /// Composition mole fractions
     pub x: [f64; NC],
+    /// Number of iterations used by the most recent call to [`density()`](Detail::density)
+    pub last_iterations: u32,
 
+    computed: ComputedFields,
+    computed_inputs: (f64, f64, f64, [f64; NC]),
+    // Residual entropy, calculated in properties()
+    s_res: f64,
     xold: [f64; MAXFLDS],
     told: f64,
     ki25: [f64; MAXFLDS],
@@ -718,6 +819,44 @@ pub struct Detail {
     ar: [[f64; 4]; 4],
     tun: [f64; NTERMS],
     n0i: [[f64; 7]; MAXFLDS],
+    eij: [[f64; MAXFLDS]; MAXFLDS],
+    uij: [[f64; MAXFLDS]; MAXFLDS],
+    kij: [[f64; MAXFLDS]; MAXFLDS],
+    gij: [[f64; MAXFLDS]; MAXFLDS],
+
+    // Scratch buffers reused by alphar
+    sum0: [f64; NTERMS],
+    sumb: [f64; NTERMS],
+    dknn: [f64; 10],
+    expn: [f64; 5],
+    coefd1: [f64; NTERMS],
+    coefd2: [f64; NTERMS],
+    coefd3: [f64; NTERMS],
+    coeft1: [f64; NTERMS],
+    coeft2: [f64; NTERMS],
+}
+
+// The state saved by `Detail::save_state()` and restored by `Detail::restore()`.
+struct SavedState {
+    properties: PropertySet,
+    computed: ComputedFields,
+    computed_inputs: (f64, f64, f64, [f64; NC]),
+    s_res: f64,
+    dp_dd_save: f64,
+    a0: [f64; 3],
+    ar: [[f64; 4]; 4],
+}
+
+// The tables calculated by `Detail::setup()`, which only depend on the constants
+// of the model and not on the composition or state.
+struct SetupTables {
+    ki25: [f64; MAXFLDS],
+    ei25: [f64; MAXFLDS],
+    bsnij2: [[[f64; 18]; MAXFLDS]; MAXFLDS],
+    kij5: [[f64; MAXFLDS]; MAXFLDS],
+    uij5: [[f64; MAXFLDS]; MAXFLDS],
+    gij5: [[f64; MAXFLDS]; MAXFLDS],
+    n0i: [[f64; 7]; MAXFLDS],
 }
 
 impl Default for Detail {
@@ -725,6 +864,10 @@ impl Default for Detail {
         Detail {
             dp_dd_save: 0.0,
             x: [0.0; NC],
+            last_iterations: 0,
+            computed: ComputedFields::default(),
+            computed_inputs: (0.0, 0.0, 0.0, [0.0; NC]),
+            s_res: 0.0,
             t: 0.0,
             p: 0.0,
             d: 0.0,
@@ -758,16 +901,58 @@ impl Default for Detail {
             csn: [0.0; NTERMS],
             tun: [0.0; NTERMS],
             n0i: [[0.0; 7]; MAXFLDS],
+            eij: [[0.0; MAXFLDS]; MAXFLDS],
+            uij: [[0.0; MAXFLDS]; MAXFLDS],
+            kij: [[0.0; MAXFLDS]; MAXFLDS],
+            gij: [[0.0; MAXFLDS]; MAXFLDS],
+            sum0: [0.0; NTERMS],
+            sumb: [0.0; NTERMS],
+            dknn: [0.0; 10],
+            expn: [0.0; 5],
+            coefd1: [0.0; NTERMS],
+            coefd2: [0.0; NTERMS],
+            coefd3: [0.0; NTERMS],
+            coeft1: [0.0; NTERMS],
+            coeft2: [0.0; NTERMS],
         }
     }
 }
 
 impl Detail {
     /// Constructs a new Detail struct
+    ///
+    /// The composition independent tables are calculated by the first call,
+    /// and copied by the following calls.
     pub fn new() -> Self {
-        let mut item: Self = Default::default();
-        item.setup();
-        item
+        static TABLES: OnceLock<SetupTables> = OnceLock::new();
+        let tables = TABLES.get_or_init(|| {
+            let mut item: Self = Default::default();
+            item.setup();
+            SetupTables {
+                ki25: item.ki25,
+                ei25: item.ei25,
+                bsnij2: item.bsnij2,
+                kij5: item.kij5,
+                uij5: item.uij5,
+                gij5: item.gij5,
+                n0i: item.n0i,
+            }
+        });
+
+        Detail {
+            ki25: tables.ki25,
+            ei25: tables.ei25,
+            bsnij2: tables.bsnij2,
+            kij5: tables.kij5,
+            uij5: tables.uij5,
+            gij5: tables.gij5,
+            n0i: tables.n0i,
+            eij: EIJ,
+            uij: UIJ,
+            kij: KIJ,
+            gij: GIJ,
+            ..Default::default()
+        }
     }
 
     /// Initialize all the constants and parameters in the DETAIL model.
@@ -925,35 +1110,14 @@ impl Detail {
         self.n0i[20][0] = 10.04639507;
         self.n0i[20][1] = -745.375;
 
-        let mut bsnij: f64;
+        self.eij = EIJ;
+        self.uij = UIJ;
+        self.kij = KIJ;
+        self.gij = GIJ;
 
         for i in 0..MAXFLDS {
             for j in 0..MAXFLDS {
-                for n in 0..18 {
-                    bsnij = 1.0;
-                    if GN[n] == 1 {
-                        bsnij = GIJ[i][j] * (GI[i] + GI[j]) / 2.0;
-                    }
-                    if QN[n] == 1 {
-                        bsnij = bsnij * QI[i] * QI[j];
-                    }
-                    if FN[n] == 1 {
-                        bsnij = bsnij * FI[i] * FI[j];
-                    }
-                    if SN[n] == 1 {
-                        bsnij = bsnij * SI[i] * SI[j];
-                    }
-                    if WN[n] == 1 {
-                        bsnij = bsnij * WI[i] * WI[j];
-                    }
-                    self.bsnij2[i][j][n] = AN[n]
-                        * (EIJ[i][j] * (EI[i] * EI[j]).sqrt()).powf(UN[n])
-                        * (KI[i] * KI[j]).powf(1.5)
-                        * bsnij;
-                }
-                self.kij5[i][j] = (KIJ[i][j].powi(5) - 1.0) * self.ki25[i] * self.ki25[j];
-                self.uij5[i][j] = (UIJ[i][j].powi(5) - 1.0) * self.ei25[i] * self.ei25[j];
-                self.gij5[i][j] = (GIJ[i][j] - 1.0) * (GI[i] + GI[j]) / 2.0;
+                self.binary_terms(i, j);
             }
         }
 
@@ -966,6 +1130,37 @@ impl Detail {
         }
     }
 
+    // Calculate the binary parameter dependent terms for the component pair i, j.
+    fn binary_terms(&mut self, i: usize, j: usize) {
+        let mut bsnij: f64;
+
+        for n in 0..18 {
+            bsnij = 1.0;
+            if GN[n] == 1 {
+                bsnij = self.gij[i][j] * (GI[i] + GI[j]) / 2.0;
+            }
+            if QN[n] == 1 {
+                bsnij = bsnij * QI[i] * QI[j];
+            }
+            if FN[n] == 1 {
+                bsnij = bsnij * FI[i] * FI[j];
+            }
+            if SN[n] == 1 {
+                bsnij = bsnij * SI[i] * SI[j];
+            }
+            if WN[n] == 1 {
+                bsnij = bsnij * WI[i] * WI[j];
+            }
+            self.bsnij2[i][j][n] = AN[n]
+                * (self.eij[i][j] * (EI[i] * EI[j]).sqrt()).powf(UN[n])
+                * (KI[i] * KI[j]).powf(1.5)
+                * bsnij;
+        }
+        self.kij5[i][j] = (self.kij[i][j].powi(5) - 1.0) * self.ki25[i] * self.ki25[j];
+        self.uij5[i][j] = (self.uij[i][j].powi(5) - 1.0) * self.ei25[i] * self.ei25[j];
+        self.gij5[i][j] = (self.gij[i][j] - 1.0) * (GI[i] + GI[j]) / 2.0;
+    }
+
     /// Sets the composition
     pub fn set_composition(&mut self, comp: &Composition) -> Result<(), CompositionError> {
         comp.check()?;
@@ -995,6 +1190,37 @@ impl Detail {
         Ok(())
     }
 
+    /// Normalizes and sets the composition
+    ///
+    /// [`set_composition()`](Detail::set_composition) accepts compositions whose sum
+    /// is within 1.0e-2 of 1.0 and uses the mole fractions as they are.
+    /// This function scales a copy of the composition so that the sum is
+    /// exactly 1.0 before setting it. The input is not modified.
+    ///
+    /// ## Error
+    /// Returns error if the composition is invalid.
+    pub fn set_composition_normalized(
+        &mut self,
+        comp: &Composition,
+    ) -> Result<(), CompositionError> {
+        let mut comp = *comp;
+        comp.normalize()?;
+        self.set_composition(&comp)
+    }
+
+    /// Sets the composition from mole percentages
+    ///
+    /// The components are divided by 100 before they are checked and set as with
+    /// [`set_composition()`](Detail::set_composition), so the sum of the input
+    /// is expected to be within 1.0 of 100.0. The input is not modified.
+    ///
+    /// ## Error
+    /// Returns error if the composition is invalid.
+    pub fn set_composition_percent(&mut self, comp: &Composition) -> Result<(), CompositionError> {
+        let comp = Composition::from_array(&comp.to_array().map(|x| x / 100.0));
+        self.set_composition(&comp)
+    }
+
     /// Calculates molar mass of the gas composition
     ///
     /// ## Returns:
@@ -1005,6 +1231,7 @@ impl Detail {
             mm += self.x[i] * item;
         }
         self.mm = mm;
+        self.mark_computed(ComputedFields::MOLAR_MASS);
         mm
     }
 
@@ -1133,9 +1360,9 @@ impl Detail {
                 sumhyp1 = 0.0;
                 sumhyp2 = 0.0;
 
-                for j in 3..7 {
-                    if TH0I[i][j] > 0.0 {
-                        th0t = TH0I[i][j] / self.t;
+                for (j, th0ij) in TH0I[i].iter().enumerate().skip(3) {
+                    if *th0ij > 0.0 {
+                        th0t = th0ij / self.t;
                         ep = th0t.exp();
                         em = 1.0 / ep;
                         hsn = (ep - em) / 2.0;
@@ -1197,17 +1424,9 @@ impl Detail {
         let mut s2;
         let mut s3;
 
-        let mut sum0: [f64; NTERMS] = [0.0; NTERMS];
-        let mut sumb: [f64; NTERMS] = [0.0; NTERMS];
-        let mut dknn: [f64; 10] = [0.0; 10];
-        let mut expn: [f64; 5] = [0.0; 5];
-
-        let mut coefd1: [f64; NTERMS] = [0.0; NTERMS];
-        let mut coefd2: [f64; NTERMS] = [0.0; NTERMS];
-        let mut coefd3: [f64; NTERMS] = [0.0; NTERMS];
-
-        let mut coeft1: [f64; NTERMS] = [0.0; NTERMS];
-        let mut coeft2: [f64; NTERMS] = [0.0; NTERMS];
+        // The scratch buffers sum0, sumb, dknn, expn, coefd1-3 and coeft1-2
+        // are fields on the struct, so they are not reallocated on every call.
+        // All their elements are overwritten below before being read.
 
         for i in 0..4 {
             for j in 0..4 {
@@ -1223,63 +1442,64 @@ impl Detail {
 
         // Precalculation of common powers and exponents of density
         let dred = self.k3 * self.d;
-        dknn[0] = 1.0;
+        self.dknn[0] = 1.0;
 
         for n in 1..10 {
-            dknn[n] = dred * dknn[n - 1];
+            self.dknn[n] = dred * self.dknn[n - 1];
         }
-        expn[0] = 1.0;
+        self.expn[0] = 1.0;
 
         for n in 1..5 {
-            expn[n] = (-dknn[n]).exp();
+            self.expn[n] = (-self.dknn[n]).exp();
         }
         let rt = RDETAIL * self.t;
 
         for n in 0..58 {
             // Contributions to the Helmholtz energy and its derivatives with respect to temperature
-            coeft1[n] = RDETAIL * (UN[n] - 1.0);
-            coeft2[n] = coeft1[n] * UN[n];
+            self.coeft1[n] = RDETAIL * (UN[n] - 1.0);
+            self.coeft2[n] = self.coeft1[n] * UN[n];
             // Contributions to the virial coefficients
-            sumb[n] = 0.0;
-            sum0[n] = 0.0;
+            self.sumb[n] = 0.0;
+            self.sum0[n] = 0.0;
             if n <= 17 {
                 sum = self.bs[n] * self.d;
                 if n >= 12 {
                     sum += -self.csn[n] * dred;
                 }
-                sumb[n] = sum * self.tun[n];
+                self.sumb[n] = sum * self.tun[n];
             }
             if n >= 12 {
                 // Contributions to the residual part of the Helmholtz energy
-                sum0[n] = self.csn[n] * dknn[BN[n]] * self.tun[n] * expn[KN[n]];
+                self.sum0[n] = self.csn[n] * self.dknn[BN[n]] * self.tun[n] * self.expn[KN[n]];
                 // Contributions to the derivatives of the Helmholtz energy with respect to density
-                bkd = BN[n] as f64 - KN[n] as f64 * dknn[KN[n]];
-                ckd = KN[n] as f64 * KN[n] as f64 * dknn[KN[n]];
-                coefd1[n] = bkd;
-                coefd2[n] = bkd * (bkd - 1.0) - ckd;
-                coefd3[n] = (bkd - 2.0) * coefd2[n] + ckd * (1.0 - KN[n] as f64 - 2.0 * bkd);
+                bkd = BN[n] as f64 - KN[n] as f64 * self.dknn[KN[n]];
+                ckd = KN[n] as f64 * KN[n] as f64 * self.dknn[KN[n]];
+                self.coefd1[n] = bkd;
+                self.coefd2[n] = bkd * (bkd - 1.0) - ckd;
+                self.coefd3[n] =
+                    (bkd - 2.0) * self.coefd2[n] + ckd * (1.0 - KN[n] as f64 - 2.0 * bkd);
             } else {
-                coefd1[n] = 0.0;
-                coefd2[n] = 0.0;
-                coefd3[n] = 0.0;
+                self.coefd1[n] = 0.0;
+                self.coefd2[n] = 0.0;
+                self.coefd3[n] = 0.0;
             }
         }
 
         for n in 0..58 {
             // Density derivatives
-            s0 = sum0[n] + sumb[n];
-            s1 = sum0[n] * coefd1[n] + sumb[n];
-            s2 = sum0[n] * coefd2[n];
-            s3 = sum0[n] * coefd3[n];
+            s0 = self.sum0[n] + self.sumb[n];
+            s1 = self.sum0[n] * self.coefd1[n] + self.sumb[n];
+            s2 = self.sum0[n] * self.coefd2[n];
+            s3 = self.sum0[n] * self.coefd3[n];
             self.ar[0][0] += rt * s0;
             self.ar[0][1] += rt * s1;
             self.ar[0][2] += rt * s2;
             self.ar[0][3] += rt * s3;
             // Temperature derivatives
             if itau > 0 {
-                self.ar[1][1] -= coeft1[n] * s1;
-                self.ar[1][0] -= coeft1[n] * s0;
-                self.ar[2][0] += coeft2[n] * s0;
+                self.ar[1][1] -= self.coeft1[n] * s1;
+                self.ar[1][0] -= self.coeft1[n] * s0;
+                self.ar[2][0] += self.coeft2[n] * s0;
                 //The following are not used, but fully functional
                 //ar(1, 2) = ar(1, 2) - CoefT1(n) * s2;
                 //ar(1, 3) = ar(1, 3) - CoefT1(n) * s3;
@@ -1300,15 +1520,48 @@ impl Detail {
     /// It is up to the user to locate the phase boundary, and thus identify the phase of the T and P inputs.
     /// If the state point is 2-phase, the output density will represent a metastable state.
     pub fn density(&mut self) -> Result<(), DensityError> {
+        self.density_with_tolerance(0.000_000_1)
+    }
+
+    /// Calculates the density and returns the compressibility factor.
+    ///
+    /// This is the fast path for gas metering, where only Z and the density are needed.
+    /// The density iteration only evaluates the density derivatives of the residual
+    /// Helmholtz energy, so the ideal gas part and the temperature derivatives
+    /// used by [`properties()`](Detail::properties) are not calculated.
+    ///
+    /// The `z` field is left at the last iteration of [`density()`](Detail::density), so Z is
+    /// recalculated with [`pressure()`](Detail::pressure) at the converged density.
+    ///
+    /// The temperature `t`, pressure `p` and composition must be set before calling this method.
+    ///
+    /// ## Returns:
+    /// - z - Compressibility factor
+    pub fn compressibility(&mut self) -> Result<f64, DensityError> {
+        self.density()?;
+        self.pressure();
+        Ok(self.z)
+    }
+
+    /// Calculate density as a function of temperature and pressure, with a given tolerance.
+    ///
+    /// This is the same iteration as [`density()`](Detail::density), which uses a tolerance of 1.0e-7
+    /// on the change in log(v). A tolerance of 1.0e-12 gives a density at machine precision.
+    /// Tighter tolerances can require more iterations.
+    pub fn density_refined(&mut self, tol: f64) -> Result<(), DensityError> {
+        self.density_with_tolerance(tol)
+    }
+
+    fn density_with_tolerance(&mut self, tolr: f64) -> Result<(), DensityError> {
         let mut dpdlv: f64;
         let mut vdiff: f64;
         let mut p2: f64;
 
+        self.last_iterations = 0;
         if self.p.abs() < EPSILON {
             self.d = 0.0;
             return Err(DensityError::PressureTooLow);
         }
-        const TOLR: f64 = 0.000_000_1;
         if self.d > -EPSILON {
             self.d = self.p / RDETAIL / self.t; // Ideal gas estimate
         } else {
@@ -1316,7 +1569,8 @@ impl Detail {
         }
         let plog = self.p.ln();
         let mut vlog = -self.d.ln();
-        for _it in 0..20 {
+        for it in 1..=20 {
+            self.last_iterations = it;
             if !(-7.0..=100.0).contains(&vlog) {
                 //ierr = 1; herr = "Calculation failed to converge in DETAIL method, ideal gas density returned.";
                 self.d = self.p / RDETAIL / self.t;
@@ -1333,8 +1587,9 @@ impl Detail {
                 dpdlv = -self.d * self.dp_dd_save; // d(p)/d[log(v)]
                 vdiff = (p2.ln() - plog) * p2 / dpdlv;
                 vlog -= vdiff;
-                if vdiff.abs() < TOLR {
+                if vdiff.abs() < tolr {
                     self.d = (-vlog).exp();
+                    self.mark_computed(ComputedFields::DENSITY);
                     return Ok(()); // Iteration converged
                 }
             }
@@ -1365,6 +1620,7 @@ impl Detail {
     /// If the density is not known, call subroutine DensityDetail first
     /// with the known values of pressure and temperature.
     pub fn properties(&mut self) {
+        let computed = self.computed_fields();
         let mm = self.molar_mass();
         self.x_terms();
 
@@ -1381,6 +1637,7 @@ impl Detail {
         self.dp_dt = self.d * RDETAIL + self.d * self.ar[1][1];
         let a = self.a0[0] + self.ar[0][0];
         self.s = -self.a0[1] - self.ar[1][0];
+        self.s_res = -self.ar[1][0];
         self.u = a + self.t * self.s;
         self.cv = -(self.a0[2] + self.ar[2][0]);
         if self.d > EPSILON {
@@ -1403,5 +1660,1844 @@ impl Detail {
         self.w = self.w.sqrt();
         self.kappa = self.w * self.w * mm / (rt * 1000.0 * self.z);
         self.d2p_dtd = 0.0;
+
+        // The pressure is recalculated from the density, so the inputs are stored again
+        self.computed_inputs = (self.t, self.p, self.d, self.x);
+        self.computed = computed | ComputedFields::PROPERTIES | ComputedFields::MOLAR_MASS;
+    }
+
+    /// Calculates the fugacity coefficient of each component at the current temperature and density.
+    ///
+    /// The coefficients are found by numerically differentiating the total residual Helmholtz
+    /// energy with respect to the amount of each component at constant temperature and volume:
+    ///
+    /// ln(φ<sub>i</sub>) = ∂(n·a<sub>r</sub>)/∂n<sub>i</sub> / (RT) - ln(Z)
+    ///
+    /// ## Returns:
+    /// - phi - Fugacity coefficients, in the same order as `x`
+    pub fn fugacity_coefficients(&mut self) -> [f64; NC] {
+        const DN: f64 = 1.0e-4;
+        let x = self.x;
+        let d = self.d;
+        let rt = RDETAIL * self.t;
+
+        self.x_terms();
+        self.alphar(0, 0);
+        let z = 1.0 + self.ar[0][1] / rt;
+
+        let mut phi = [0.0; NC];
+        for (i, xi) in x.iter().enumerate() {
+            // Use a forward difference for components that are (almost) absent
+            let dn_low = if *xi > DN { -DN } else { 0.0 };
+            let nar_low = self.residual_helmholtz_added(&x, d, i, dn_low);
+            let nar_high = self.residual_helmholtz_added(&x, d, i, DN);
+            let dnar_dn = (nar_high - nar_low) / (DN - dn_low);
+            phi[i] = (dnar_dn / rt - z.ln()).exp();
+        }
+
+        self.x = x;
+        self.d = d;
+        phi
+    }
+
+    // Residual Helmholtz energy (J) of 1 + dn mol of mixture, where dn mol of
+    // component i has been added at constant total volume.
+    fn residual_helmholtz_added(&mut self, x: &[f64; NC], d: f64, i: usize, dn: f64) -> f64 {
+        let n = 1.0 + dn;
+        for (xj, x0) in self.x.iter_mut().zip(x.iter()) {
+            *xj = x0 / n;
+        }
+        self.x[i] = (x[i] + dn) / n;
+        self.d = d * n;
+        self.x_terms();
+        self.alphar(0, 0);
+        n * self.ar[0][0]
+    }
+
+    /// Calculates the residual of the Gibbs-Duhem relation for the fugacity coefficients.
+    ///
+    /// At constant temperature and pressure, Σ x<sub>i</sub>·d(ln φ<sub>i</sub>) = 0 for any change
+    /// in composition. For each component with a mole fraction above 1.0e-3, the amount of that
+    /// component is changed by ±1.0e-3 mol per mol of mixture, the density is solved at the current
+    /// temperature and pressure, and the derivatives of ln φ<sub>i</sub> are calculated with
+    /// central differences from [`fugacity_coefficients()`](Detail::fugacity_coefficients).
+    ///
+    /// The residual should be close to zero for a consistent equation of state, so it can be
+    /// used as a test oracle for changes to the fugacity calculation or the binary parameters.
+    /// It is not exactly zero because of the finite differences.
+    /// The composition and state of `self` are restored afterwards.
+    ///
+    /// ## Returns:
+    /// - r - Largest magnitude of Σ x<sub>i</sub>·∂(ln φ<sub>i</sub>)/∂n<sub>k</sub>, or NaN if a density calculation fails
+    pub fn gibbs_duhem_residual(&mut self) -> f64 {
+        const DN: f64 = 1.0e-3;
+        let saved = self.save_state();
+        let x = self.x;
+
+        let mut residual: Result<f64, DensityError> = Ok(0.0);
+        for k in (0..NC).filter(|&k| x[k] > DN) {
+            residual = residual.and_then(|r| {
+                let high = self.ln_fugacity_coefficients_added(&x, k, DN)?;
+                let low = self.ln_fugacity_coefficients_added(&x, k, -DN)?;
+                let rk: f64 = (0..NC)
+                    .map(|i| x[i] * (high[i] - low[i]) / (2.0 * DN))
+                    .sum();
+                Ok(r.max(rk.abs()))
+            });
+        }
+
+        self.x = x;
+        self.restore(&saved);
+        residual.unwrap_or(f64::NAN)
+    }
+
+    // Logarithm of the fugacity coefficients at the current temperature and pressure,
+    // after dn mol of component k has been added to 1 mol of the mixture x.
+    fn ln_fugacity_coefficients_added(
+        &mut self,
+        x: &[f64; NC],
+        k: usize,
+        dn: f64,
+    ) -> Result<[f64; NC], DensityError> {
+        let n = 1.0 + dn;
+        for (xj, x0) in self.x.iter_mut().zip(x.iter()) {
+            *xj = x0 / n;
+        }
+        self.x[k] = (x[k] + dn) / n;
+        self.d = 0.0;
+        self.density()?;
+        Ok(self.fugacity_coefficients().map(f64::ln))
+    }
+
+    /// Calculates the fugacity of the mixture at the current temperature and density.
+    ///
+    /// The fugacity is derived from the residual Gibbs energy:
+    ///
+    /// ln(f/P) = G<sub>r</sub>/(RT) = a<sub>r</sub>/(RT) + Z - 1 - ln(Z)
+    ///
+    /// ## Returns:
+    /// - f - Fugacity of the mixture (kPa)
+    pub fn mixture_fugacity(&mut self) -> f64 {
+        self.x_terms();
+        self.alphar(0, 0);
+        let rt = RDETAIL * self.t;
+        let z = 1.0 + self.ar[0][1] / rt;
+        let p = self.d * rt * z;
+        p * (self.ar[0][0] / rt + z - 1.0 - z.ln()).exp()
+    }
+
+    /// Calculates pressure at the given temperature and density.
+    ///
+    /// Unlike [`pressure()`](Detail::pressure), the stored state is left unchanged.
+    ///
+    /// ## Returns:
+    /// - p - Pressure (kPa)
+    pub fn pressure_at(&mut self, t: f64, d: f64) -> f64 {
+        let saved = self.save_state();
+        self.t = t;
+        self.d = d;
+        let p = self.pressure();
+        self.restore(&saved);
+        p
+    }
+
+    /// Calculates the first derivative of pressure with respect to density
+    /// at constant temperature, at the given temperature and density.
+    ///
+    /// This is the same derivative that [`pressure()`](Detail::pressure) uses internally
+    /// in the density iteration. The stored state is left unchanged.
+    ///
+    /// ## Returns:
+    /// - dp_dd - d(P)/d(D) (kPa/(mol/l))
+    pub fn dp_dd_at(&mut self, t: f64, d: f64) -> f64 {
+        let saved = self.save_state();
+        self.t = t;
+        self.d = d;
+        self.pressure();
+        let dp_dd = self.dp_dd_save;
+        self.restore(&saved);
+        dp_dd
+    }
+
+    /// Calculates the real gas and the ideal gas speed of sound at the current temperature and density.
+    ///
+    /// This function calls [`properties()`](Detail::properties), so all the property fields are updated.
+    /// The ideal gas speed of sound is calculated from the ideal gas heat capacities:
+    ///
+    /// w<sub>0</sub> = sqrt(γ<sub>0</sub>·R·T·1000/M), where γ<sub>0</sub> = c<sub>p0</sub>/c<sub>v0</sub>
+    ///
+    /// ## Returns:
+    /// - (w, w0) - Real gas and ideal gas speed of sound (m/s)
+    pub fn speed_of_sound_comparison(&mut self) -> (f64, f64) {
+        self.properties();
+        let cv0 = -self.a0[2];
+        let cp0 = cv0 + RDETAIL;
+        let w0 = (cp0 / cv0 * RDETAIL * self.t * 1000.0 / self.mm).sqrt();
+        (self.w, w0)
+    }
+
+    /// Calculates the mass density of the gas at the current density.
+    ///
+    /// The molar mass is recalculated from the current composition.
+    ///
+    /// ## Returns:
+    /// - rho - Mass density (kg/m³)
+    pub fn mass_density(&mut self) -> f64 {
+        // mol/l * g/mol = g/l = kg/m³
+        self.d * self.molar_mass()
+    }
+
+    /// Calculates the mass density from a measured mass flow and volume flow.
+    ///
+    /// The flows must be measured at the same conditions, e.g. mass flow in kg/s and
+    /// volume flow in m³/s at line conditions.
+    ///
+    /// ## Returns:
+    /// - rho - Mass density (kg/m³)
+    pub fn density_from_flows(mass_flow: f64, volume_flow: f64) -> f64 {
+        mass_flow / volume_flow
+    }
+
+    /// Calculates the relative deviation between the mass density implied by
+    /// the measured flows and the [`mass_density()`](Detail::mass_density) at the current state.
+    ///
+    /// The density must be calculated before calling this function.
+    ///
+    /// ## Returns:
+    /// - deviation - (rho<sub>flows</sub> - rho<sub>eos</sub>)/rho<sub>eos</sub>
+    pub fn flow_consistency(&mut self, mass_flow: f64, volume_flow: f64) -> f64 {
+        let rho_eos = self.mass_density();
+        (Self::density_from_flows(mass_flow, volume_flow) - rho_eos) / rho_eos
+    }
+
+    /// Returns the results as a map from property name to value.
+    ///
+    /// The map is intended for logging and should be read after
+    /// [`properties()`](Detail::properties) has been called.
+    pub fn to_property_map(&self) -> BTreeMap<&'static str, f64> {
+        BTreeMap::from([
+            ("temperature", self.t),
+            ("pressure", self.p),
+            ("density", self.d),
+            ("compressibility", self.z),
+            ("molar_mass", self.mm),
+            ("dp_dd", self.dp_dd),
+            ("d2p_dd2", self.d2p_dd2),
+            ("dp_dt", self.dp_dt),
+            ("internal_energy", self.u),
+            ("enthalpy", self.h),
+            ("entropy", self.s),
+            ("isochoric_heat_capacity", self.cv),
+            ("isobaric_heat_capacity", self.cp),
+            ("speed_of_sound", self.w),
+            ("gibbs_energy", self.g),
+            ("joule_thomson", self.jt),
+            ("isentropic_exponent", self.kappa),
+        ])
+    }
+
+    /// Returns the binary interaction parameters for the component pair `i`, `j`.
+    ///
+    /// The component indices are the same as in `x`, e.g. 0 is methane and 2 is carbon dioxide.
+    /// The parameter tables only hold values for `i < j`, so the order of `i` and `j` does not matter.
+    /// For `i == j` all parameters are 1.0.
+    ///
+    /// # Panics
+    /// Panics if `i` or `j` is not a valid component index.
+    pub fn binary_parameters(i: usize, j: usize) -> BinaryParams {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        BinaryParams {
+            eij: EIJ[i][j],
+            uij: UIJ[i][j],
+            kij: KIJ[i][j],
+            gij: GIJ[i][j],
+        }
+    }
+
+    /// Overrides one of the binary interaction parameters for the component pair `i`, `j`.
+    ///
+    /// The component indices are the same as in `x`. As in [`binary_parameters()`](Detail::binary_parameters),
+    /// the order of `i` and `j` does not matter.
+    ///
+    /// **Note:** Changing the binary interaction parameters deviates from the
+    /// standard AGA8 DETAIL equation of state. This is intended for research on mixing rules.
+    ///
+    /// # Panics
+    /// Panics if `i` or `j` is not a valid component index.
+    pub fn set_binary_parameter(&mut self, i: usize, j: usize, kind: BinaryParamKind, value: f64) {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        match kind {
+            BinaryParamKind::Eij => self.eij[i][j] = value,
+            BinaryParamKind::Uij => self.uij[i][j] = value,
+            BinaryParamKind::Kij => self.kij[i][j] = value,
+            BinaryParamKind::Gij => self.gij[i][j] = value,
+        }
+        self.binary_terms(i, j);
+
+        // Force x_terms to recalculate the composition dependent terms
+        self.xold = [-1.0; MAXFLDS];
+    }
+
+    /// Calculates the apparent molar mass of the gas at the current state.
+    ///
+    /// The apparent molar mass is the molar mass that, used in the ideal gas law,
+    /// gives the real gas mass density:
+    ///
+    /// M<sub>app</sub> = ρ·R·T/P = M/Z
+    ///
+    /// The compressibility factor must be calculated before calling this function,
+    /// e.g. with [`density()`](Detail::density). [`molar_mass()`](Detail::molar_mass) is not affected.
+    ///
+    /// ## Returns:
+    /// - mm_app - Apparent molar mass (g/mol)
+    pub fn apparent_molar_mass(&mut self) -> f64 {
+        self.molar_mass() / self.z
+    }
+
+    /// Returns a copy of the temperature, pressure and the calculated properties.
+    pub fn property_set(&self) -> PropertySet {
+        PropertySet {
+            t: self.t,
+            p: self.p,
+            d: self.d,
+            z: self.z,
+            mm: self.mm,
+            dp_dd: self.dp_dd,
+            d2p_dd2: self.d2p_dd2,
+            d2p_dtd: self.d2p_dtd,
+            dp_dt: self.dp_dt,
+            u: self.u,
+            h: self.h,
+            s: self.s,
+            cv: self.cv,
+            cp: self.cp,
+            w: self.w,
+            g: self.g,
+            jt: self.jt,
+            kappa: self.kappa,
+        }
+    }
+
+    /// Returns a record of the composition, temperature, pressure and the calculated properties.
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self) -> crate::ResultSnapshot {
+        crate::ResultSnapshot {
+            composition: Composition::from_array(&self.x),
+            properties: self.property_set(),
+        }
+    }
+
+    // Saves the temperature, pressure, the calculated properties and the computed field flags,
+    // and the Helmholtz energy terms that some of the outputs are calculated from.
+    fn save_state(&self) -> SavedState {
+        SavedState {
+            properties: self.property_set(),
+            computed: self.computed,
+            computed_inputs: self.computed_inputs,
+            s_res: self.s_res,
+            dp_dd_save: self.dp_dd_save,
+            a0: self.a0,
+            ar: self.ar,
+        }
+    }
+
+    // Restores a state saved with `save_state()`.
+    fn restore(&mut self, saved: &SavedState) {
+        let state = &saved.properties;
+        self.t = state.t;
+        self.p = state.p;
+        self.d = state.d;
+        self.z = state.z;
+        self.mm = state.mm;
+        self.dp_dd = state.dp_dd;
+        self.d2p_dd2 = state.d2p_dd2;
+        self.d2p_dtd = state.d2p_dtd;
+        self.dp_dt = state.dp_dt;
+        self.u = state.u;
+        self.h = state.h;
+        self.s = state.s;
+        self.cv = state.cv;
+        self.cp = state.cp;
+        self.w = state.w;
+        self.g = state.g;
+        self.jt = state.jt;
+        self.kappa = state.kappa;
+        self.computed = saved.computed;
+        self.computed_inputs = saved.computed_inputs;
+        self.s_res = saved.s_res;
+        self.dp_dd_save = saved.dp_dd_save;
+        self.a0 = saved.a0;
+        self.ar = saved.ar;
+    }
+
+    /// Calculates the properties along an isobar.
+    ///
+    /// The temperature is stepped from `t_min` to `t_max` in `points` evenly spaced steps.
+    /// The density at each point is calculated using the density of the previous point
+    /// as the initial estimate. Points where the density calculation fails are left out.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Returns:
+    /// - Vec of (t, properties) - Temperature (K) and the properties at that temperature
+    pub fn isobar(
+        &mut self,
+        p: f64,
+        t_min: f64,
+        t_max: f64,
+        points: usize,
+    ) -> Vec<(f64, PropertySet)> {
+        let saved = self.save_state();
+        let mut result = Vec::with_capacity(points);
+
+        self.p = p;
+        self.d = 0.0;
+        for t in linspace(t_min, t_max, points) {
+            self.t = t;
+            // A negative density is used as the initial estimate
+            self.d = -self.d;
+            if self.density().is_ok() {
+                self.properties();
+                result.push((t, self.property_set()));
+            } else {
+                self.d = 0.0;
+            }
+        }
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates enthalpy and pressure coordinates along an isotherm, for a pressure-enthalpy diagram.
+    ///
+    /// The pressure is stepped from `p_min` to `p_max` in `points` evenly spaced steps.
+    /// The density at each point is calculated using the density of the previous point
+    /// as the initial estimate. Points where the density calculation fails are left out.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Returns:
+    /// - Vec of (h, p) - Enthalpy (J/mol) and pressure (kPa)
+    pub fn ph_diagram_isotherm(
+        &mut self,
+        t: f64,
+        p_min: f64,
+        p_max: f64,
+        points: usize,
+    ) -> Vec<(f64, f64)> {
+        let saved = self.save_state();
+        let mut result = Vec::with_capacity(points);
+
+        self.t = t;
+        self.d = 0.0;
+        for p in linspace(p_min, p_max, points) {
+            self.p = p;
+            // A negative density is used as the initial estimate
+            self.d = -self.d;
+            if self.density().is_ok() {
+                self.properties();
+                result.push((self.h, p));
+            } else {
+                self.d = 0.0;
+            }
+        }
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the density of [dry air](Composition::DRY_AIR) at the given
+    /// temperature and pressure.
+    ///
+    /// The composition and state of `self` are restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - p - Pressure (kPa)
+    ///
+    /// ## Returns:
+    /// - d - Molar density of air (mol/l)
+    pub fn air_density(&mut self, t: f64, p: f64) -> Result<f64, DensityError> {
+        let saved = self.save_state();
+        let x = self.x;
+
+        self.x = Composition::DRY_AIR.to_array();
+        self.t = t;
+        self.p = p;
+        self.d = 0.0;
+        let result = self.density().map(|_| self.d);
+
+        self.x = x;
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the derivative of the compressibility factor with respect to
+    /// temperature at constant pressure.
+    ///
+    /// From Z = P/(d·R·T) at constant P:
+    ///
+    /// ∂Z/∂T = -Z/T - Z/d·(∂d/∂T)<sub>P</sub>, where (∂d/∂T)<sub>P</sub> = -(∂P/∂T)<sub>d</sub>/(∂P/∂d)<sub>T</sub>
+    ///
+    /// which gives ∂Z/∂T = Z·((∂P/∂T)<sub>d</sub>/(d·(∂P/∂d)<sub>T</sub>) - 1/T).
+    ///
+    /// [`properties()`](Detail::properties) must be called first.
+    ///
+    /// ## Returns:
+    /// - dz_dt - (∂Z/∂T)<sub>P</sub> (1/K)
+    pub fn dz_dt_p(&self) -> f64 {
+        self.z * (self.dp_dt / (self.d * self.dp_dd) - 1.0 / self.t)
+    }
+
+    /// Calculates the molar enthalpy at base conditions.
+    ///
+    /// The enthalpy is relative to the reference state of the equation of state,
+    /// so only differences in enthalpy are meaningful.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - base_t - Base temperature (K)
+    /// - base_p - Base pressure (kPa)
+    ///
+    /// ## Returns:
+    /// - h - Enthalpy at base conditions (J/mol)
+    pub fn standard_enthalpy(&mut self, base_t: f64, base_p: f64) -> Result<f64, DensityError> {
+        let saved = self.save_state();
+
+        self.t = base_t;
+        self.p = base_p;
+        self.d = 0.0;
+        let result = self.density().map(|_| {
+            self.properties();
+            self.h
+        });
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the derivative of temperature with respect to pressure at constant entropy.
+    ///
+    /// (∂T/∂P)<sub>s</sub> = T·v·α/c<sub>p</sub>, where v = 1/d and the thermal expansion
+    /// coefficient α = (∂P/∂T)<sub>d</sub>/(d·(∂P/∂d)<sub>T</sub>).
+    ///
+    /// [`properties()`](Detail::properties) must be called first.
+    ///
+    /// ## Returns:
+    /// - dt_dp - (∂T/∂P)<sub>s</sub> (K/kPa)
+    pub fn dt_dp_isentropic(&self) -> f64 {
+        let alpha = self.dp_dt / (self.d * self.dp_dd);
+        self.t * alpha / (self.d * self.cp)
+    }
+
+    /// Calculates the isothermal Joule-Thomson coefficient.
+    ///
+    /// μ<sub>T</sub> = (∂h/∂P)<sub>T</sub> = -c<sub>p</sub>·μ<sub>JT</sub>, which is equal to
+    /// v - T·(∂v/∂T)<sub>P</sub>.
+    ///
+    /// [`properties()`](Detail::properties) must be called first.
+    ///
+    /// ## Returns:
+    /// - mu_t - Isothermal Joule-Thomson coefficient (J/(mol·kPa))
+    pub fn isothermal_jt(&self) -> f64 {
+        -self.cp * self.jt
     }
+
+    /// Calculates the density and pressure where the compressibility factor
+    /// equals `target_z` at the temperature `t`.
+    ///
+    /// Newton's method is used on the density, starting from a low density, so the
+    /// lowest density solution is usually found. Z is not a monotonic function of density,
+    /// and near the critical region there can be several densities with the same Z.
+    /// Z approaches 1.0 as the density approaches zero, so Z = 1.0 gives the ideal gas limit.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - target_z - Compressibility factor
+    ///
+    /// ## Returns:
+    /// Sets `t`, `d`, `p` and `z`.
+    ///
+    /// ## Error
+    /// Returns [`DensityError::IterationFail`] if the iteration does not converge.
+    pub fn solve_for_z(&mut self, t: f64, target_z: f64) -> Result<(), DensityError> {
+        self.solve_for_z_from(t, target_z, 1.0e-3)
+    }
+
+    // Newton's method for solve_for_z(), starting from the density d0.
+    fn solve_for_z_from(&mut self, t: f64, target_z: f64, d0: f64) -> Result<(), DensityError> {
+        const TOLR: f64 = 1.0e-10;
+        let rt = RDETAIL * t;
+
+        self.t = t;
+        self.d = d0;
+        for _ in 0..50 {
+            self.p = self.pressure();
+            let dz_dd = (self.dp_dd_save - rt * self.z) / (rt * self.d);
+            let dd = (self.z - target_z) / dz_dd;
+            self.d = if self.d - dd > 0.0 {
+                self.d - dd
+            } else {
+                self.d / 2.0
+            };
+            if (self.z - target_z).abs() < TOLR {
+                self.p = self.pressure();
+                return Ok(());
+            }
+        }
+        Err(DensityError::IterationFail)
+    }
+
+    /// Calculates the pressure on the ideal curve, where Z = 1, at each of the given temperatures.
+    ///
+    /// Z approaches 1.0 as the density approaches zero. Below the Boyle temperature the second
+    /// virial coefficient is negative, so Z first drops below 1.0 and then returns to 1.0 at a
+    /// higher density. The density is scanned in steps of 0.5 mol/l up to 50 mol/l to find where
+    /// Z returns to 1.0, and the point is refined with [`solve_for_z()`](Detail::solve_for_z).
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - temperatures - Temperatures (K)
+    ///
+    /// ## Returns:
+    /// - Vec of (t, p) - Temperature (K) and pressure (kPa) on the ideal curve,
+    ///   or `None` if Z does not return to 1.0 at that temperature
+    pub fn ideal_curve(&mut self, temperatures: &[f64]) -> Vec<(f64, Option<f64>)> {
+        const STEP: f64 = 0.5;
+        const D_MAX: f64 = 50.0;
+        let saved = self.save_state();
+
+        let result = temperatures
+            .iter()
+            .map(|&t| {
+                self.t = t;
+                let mut d = STEP;
+                let mut below = false;
+                while d <= D_MAX {
+                    self.d = d;
+                    self.pressure();
+                    if self.z < 1.0 {
+                        below = true;
+                    } else if below {
+                        let p = self
+                            .solve_for_z_from(t, 1.0, d)
+                            .ok()
+                            .map(|_| self.p)
+                            .filter(|&p| p > 0.0);
+                        return (t, p);
+                    }
+                    d += STEP;
+                }
+                (t, None)
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the ideal gas Helmholtz energy and its temperature derivatives
+    /// at the current temperature and density.
+    ///
+    /// The entropy is s = -a0\[1\] - a<sup>r</sup><sub>T</sub>, and the isochoric heat capacity
+    /// is c<sub>v</sub> = -a0\[2\] - T·a<sup>r</sup><sub>TT</sub>, see [`properties()`](Detail::properties).
+    ///
+    /// ## Returns:
+    /// - a0\[0\] - Ideal gas Helmholtz energy (J/mol)
+    /// - a0\[1\] - ∂a0/∂T (J/(mol-K))
+    /// - a0\[2\] - T·∂<sup>2</sup>a0/∂T<sup>2</sup> (J/(mol-K))
+    pub fn ideal_helmholtz(&mut self) -> [f64; 3] {
+        self.alpha0_detail();
+        self.a0
+    }
+
+    /// Calculates the compressibility factor at each of the given pressures.
+    ///
+    /// The temperature is set once, and the density at each pressure is calculated
+    /// using the density at the previous pressure as the initial estimate.
+    /// Only the density is calculated, not the rest of the properties.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - pressures - Pressures (kPa)
+    ///
+    /// ## Returns:
+    /// - Vec of z - Compressibility factor at each pressure, or the density error
+    pub fn z_table(&mut self, t: f64, pressures: &[f64]) -> Vec<Result<f64, DensityError>> {
+        let saved = self.save_state();
+
+        self.t = t;
+        self.d = 0.0;
+        let result = pressures
+            .iter()
+            .map(|&p| {
+                self.p = p;
+                // A negative density is used as the initial estimate
+                self.d = -self.d;
+                let z = self.density().map(|_| self.z);
+                if z.is_err() {
+                    self.d = 0.0;
+                }
+                z
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates a table of volume correction factors for line conditions.
+    ///
+    /// The volume correction factor converts a volume at line conditions to base conditions,
+    /// VCF = V<sub>base</sub>/V<sub>line</sub> = (P·T<sub>base</sub>·Z<sub>base</sub>)/(P<sub>base</sub>·T·Z).
+    /// The compressibility factors are calculated with [`z_table()`](Detail::z_table).
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - temperatures - Line temperatures (K)
+    /// - pressures - Line pressures (kPa)
+    /// - base_t - Base temperature (K)
+    /// - base_p - Base pressure (kPa)
+    ///
+    /// ## Returns:
+    /// - Vec of rows - One row for each temperature, with the volume correction factor
+    ///   at each pressure. A factor is NaN if the density calculation failed.
+    pub fn vcf_table(
+        &mut self,
+        temperatures: &[f64],
+        pressures: &[f64],
+        base_t: f64,
+        base_p: f64,
+    ) -> Vec<Vec<f64>> {
+        let z_base = self.z_table(base_t, &[base_p])[0]
+            .as_ref()
+            .map_or(f64::NAN, |z| *z);
+
+        temperatures
+            .iter()
+            .map(|&t| {
+                self.z_table(t, pressures)
+                    .into_iter()
+                    .zip(pressures)
+                    .map(|(z, p)| match z {
+                        Ok(z) => p * base_t * z_base / (base_p * t * z),
+                        Err(_) => f64::NAN,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Calculates the density and how much it deviates from the ideal gas density.
+    ///
+    /// The deviation is (d - d<sub>ideal</sub>)/d<sub>ideal</sub>, where d<sub>ideal</sub> = P/(R·T).
+    /// Since d = P/(Z·R·T), this is equal to 1/Z - 1. A positive value means that the gas
+    /// is denser than an ideal gas.
+    ///
+    /// ## Returns:
+    /// - deviation - Relative deviation from the ideal gas density
+    pub fn nonideality_factor(&mut self) -> Result<f64, DensityError> {
+        self.density()?;
+        let d_ideal = self.p / (RDETAIL * self.t);
+        Ok((self.d - d_ideal) / d_ideal)
+    }
+
+    /// Calculates the composition dependent terms of the equation of state.
+    ///
+    /// The terms are cached, and [`pressure()`](Detail::pressure) and
+    /// [`properties()`](Detail::properties) only recalculate them when a mole fraction
+    /// in `x` has changed by more than 1.0e-7 since the previous call. Smaller changes
+    /// to `x` use the cached terms. This function always recalculates the terms
+    /// for the current composition.
+    pub fn prepare_composition(&mut self) {
+        self.xold = [-1.0; MAXFLDS];
+        self.x_terms();
+    }
+
+    /// Returns the calculated energies, entropy and heat capacities per unit mass.
+    ///
+    /// The molar properties are divided by the molar mass `mm`,
+    /// J/kg = J/mol ÷ (g/mol) × 1000.
+    ///
+    /// [`properties()`](Detail::properties) must be called first.
+    pub fn specific_properties(&self) -> SpecificProperties {
+        let factor = 1000.0 / self.mm;
+        SpecificProperties {
+            u: self.u * factor,
+            h: self.h * factor,
+            s: self.s * factor,
+            cv: self.cv * factor,
+            cp: self.cp * factor,
+            g: self.g * factor,
+        }
+    }
+
+    /// Calculates the density and the Joule-Thomson coefficient.
+    ///
+    /// Only the derivatives needed for the Joule-Thomson coefficient are calculated,
+    /// and only `d` and `z` are updated in `self`.
+    ///
+    /// ## Returns:
+    /// - jt - Joule-Thomson coefficient (K/kPa)
+    pub fn joule_thomson(&mut self) -> Result<f64, DensityError> {
+        self.density()?;
+        if self.d <= EPSILON {
+            return Ok(1.0E+20);
+        }
+        self.alpha0_detail();
+        self.alphar(2, 2);
+
+        let rt = RDETAIL * self.t;
+        let dp_dd = rt + 2.0 * self.ar[0][1] + self.ar[0][2];
+        let dp_dt = self.d * RDETAIL + self.d * self.ar[1][1];
+        let cv = -(self.a0[2] + self.ar[2][0]);
+        let cp = cv + self.t * (dp_dt / self.d).powi(2) / dp_dd;
+        Ok((self.t / self.d * dp_dt / dp_dd - 1.0) / cp / self.d)
+    }
+
+    /// Calculates the properties along an isochore.
+    ///
+    /// The density is held at `d`, and the temperature is stepped from `t_min` to `t_max`
+    /// in `points` evenly spaced steps. The pressure and the rest of the properties
+    /// are calculated at each point. The state of `self` is restored afterwards.
+    ///
+    /// ## Returns:
+    /// - Vec of (t, properties) - Temperature (K) and the properties at that temperature
+    pub fn isochore(
+        &mut self,
+        d: f64,
+        t_min: f64,
+        t_max: f64,
+        points: usize,
+    ) -> Vec<(f64, PropertySet)> {
+        let saved = self.save_state();
+
+        self.d = d;
+        let result = linspace(t_min, t_max, points)
+            .map(|t| {
+                self.t = t;
+                self.p = self.pressure();
+                self.properties();
+                (t, self.property_set())
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Returns the outputs that are up to date with the current `t`, `p`, `d` and `x`.
+    ///
+    /// Methods that restore the state of `self` afterwards also restore the flags.
+    ///
+    /// # Example
+    /// ```
+    /// use aga8::detail::{ComputedFields, Detail};
+    ///
+    /// let mut aga8_test = Detail::new();
+    /// aga8_test.x[0] = 1.0;
+    /// aga8_test.t = 300.0;
+    /// aga8_test.p = 1_000.0;
+    /// aga8_test.density().unwrap();
+    ///
+    /// assert!(aga8_test.computed_fields().contains(ComputedFields::DENSITY));
+    /// assert!(!aga8_test.computed_fields().contains(ComputedFields::PROPERTIES));
+    /// ```
+    pub fn computed_fields(&self) -> ComputedFields {
+        if self.computed_inputs == (self.t, self.p, self.d, self.x) {
+            self.computed
+        } else {
+            ComputedFields::default()
+        }
+    }
+
+    // Sets the flags in `fields`. The other flags are cleared if the inputs have changed.
+    fn mark_computed(&mut self, fields: ComputedFields) {
+        self.computed = self.computed_fields() | fields;
+        self.computed_inputs = (self.t, self.p, self.d, self.x);
+    }
+
+    /// Calculates the isentropic bulk modulus.
+    ///
+    /// K<sub>s</sub> = ρ·w<sup>2</sup>, where ρ = d·mm is the mass density in kg/m³,
+    /// which gives K<sub>s</sub> in Pa. The result is divided by 1000 to get kPa.
+    ///
+    /// [`properties()`](Detail::properties) must be called first.
+    ///
+    /// ## Returns:
+    /// - k_s - Isentropic bulk modulus (kPa)
+    pub fn bulk_modulus(&self) -> f64 {
+        self.d * self.mm * self.w.powi(2) / 1000.0
+    }
+
+    /// Calculates the characteristic acoustic impedance.
+    ///
+    /// Z = ρ·w, where ρ = d·mm is the mass density in kg/m³.
+    ///
+    /// [`properties()`](Detail::properties) must be called first.
+    ///
+    /// ## Returns:
+    /// - z - Acoustic impedance (kg/(m²·s))
+    pub fn acoustic_impedance(&self) -> f64 {
+        self.d * self.mm * self.w
+    }
+
+    /// Calculates the compressibility factor at common reference conditions.
+    ///
+    /// The reference conditions are
+    /// - 60 °F and 14.73 psia (288.706 K and 101.560 kPa)
+    /// - 15 °C and 101.325 kPa
+    /// - 0 °C and 101.325 kPa
+    /// - 20 °C and 101.325 kPa
+    ///
+    /// Conditions where the density calculation fails are left out.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Returns:
+    /// - Vec of (t, p, z) - Temperature (K), pressure (kPa) and compressibility factor
+    pub fn reference_z_points(&mut self) -> Vec<(f64, f64, f64)> {
+        const CONDITIONS: [(f64, f64); 4] = [
+            (288.705_555_6, 101.559_77),
+            (288.15, 101.325),
+            (273.15, 101.325),
+            (293.15, 101.325),
+        ];
+        let saved = self.save_state();
+
+        let result = CONDITIONS
+            .iter()
+            .filter_map(|&(t, p)| {
+                self.t = t;
+                self.p = p;
+                self.d = 0.0;
+                self.density().ok().map(|_| (t, p, self.z))
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the standard uncertainty of the density caused by uncertainties
+    /// in the composition.
+    ///
+    /// The sensitivity ∂d/∂x<sub>i</sub> of the density to each mole fraction is found
+    /// numerically by perturbing one component at a time, without renormalizing the others.
+    /// The uncertainties are assumed to be small and independent, so they are propagated
+    /// linearly and combined in quadrature:
+    ///
+    /// σ<sub>d</sub> = √(Σ (∂d/∂x<sub>i</sub>·σ<sub>i</sub>)<sup>2</sup>)
+    ///
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - sigma - Standard uncertainty of each mole fraction, in the order of `x`
+    ///
+    /// ## Returns:
+    /// - sigma_d - Standard uncertainty of the molar density (mol/l)
+    pub fn density_uncertainty(&mut self, sigma: &[f64; NC]) -> Result<f64, DensityError> {
+        const DX: f64 = 1.0e-4;
+        let saved = self.save_state();
+        let x = self.x;
+
+        let mut density_at = |x: [f64; NC]| {
+            self.x = x;
+            self.d = 0.0;
+            self.density().map(|_| self.d)
+        };
+        let mut variance = 0.0;
+        for (i, s) in sigma.iter().enumerate() {
+            if *s == 0.0 {
+                continue;
+            }
+            let (mut x_plus, mut x_minus) = (x, x);
+            x_plus[i] += DX;
+            x_minus[i] = f64::max(x[i] - DX, 0.0);
+            let dd_dx = match (density_at(x_plus), density_at(x_minus)) {
+                (Ok(d_plus), Ok(d_minus)) => (d_plus - d_minus) / (x_plus[i] - x_minus[i]),
+                (Err(e), _) | (_, Err(e)) => {
+                    self.x = x;
+                    self.restore(&saved);
+                    return Err(e);
+                }
+            };
+            variance += (dd_dx * s).powi(2);
+        }
+
+        self.x = x;
+        self.restore(&saved);
+        Ok(variance.sqrt())
+    }
+
+    /// Calculates the spinodal densities at the temperature `t`.
+    ///
+    /// The spinodal densities are where d(P)/d(D) is zero, and between them the
+    /// isotherm is mechanically unstable. The density is scanned from zero up to
+    /// 50 mol/l, and the first region with negative d(P)/d(D) is refined with bisection.
+    /// The vapor spinodal is bracketed from zero density, where d(P)/d(D) = RT is positive.
+    /// The stored state is left unchanged.
+    ///
+    /// ## Returns:
+    /// - Some((d_vapor, d_liquid)) - The vapor and liquid spinodal densities (mol/l).
+    ///   d_liquid is NaN if d(P)/d(D) is still negative at 50 mol/l.
+    /// - None - If d(P)/d(D) is positive along the whole isotherm, as it is above the critical temperature
+    pub fn spinodal_densities(&mut self, t: f64) -> Option<(f64, f64)> {
+        const D_MAX: f64 = 50.0;
+        const POINTS: usize = 1000;
+
+        let grid: Vec<f64> = linspace(D_MAX / POINTS as f64, D_MAX, POINTS).collect();
+        let dp_dd: Vec<f64> = grid.iter().map(|&d| self.dp_dd_at(t, d)).collect();
+
+        let first = dp_dd.iter().position(|&dp_dd| dp_dd <= 0.0)?;
+        let d_low = if first > 0 { grid[first - 1] } else { 0.0 };
+        let d_vapor = self.spinodal_root(t, d_low, grid[first]);
+        let d_liquid = match dp_dd[first..].iter().position(|&dp_dd| dp_dd > 0.0) {
+            Some(last) => self.spinodal_root(t, grid[first + last], grid[first + last - 1]),
+            None => f64::NAN,
+        };
+        Some((d_vapor, d_liquid))
+    }
+
+    // Bisection for the density where d(P)/d(D) = 0, between a density with positive
+    // d(P)/d(D) and a density with negative d(P)/d(D).
+    fn spinodal_root(&mut self, t: f64, mut d_pos: f64, mut d_neg: f64) -> f64 {
+        for _ in 0..60 {
+            let d = 0.5 * (d_pos + d_neg);
+            if self.dp_dd_at(t, d) > 0.0 {
+                d_pos = d;
+            } else {
+                d_neg = d;
+            }
+        }
+        0.5 * (d_pos + d_neg)
+    }
+
+    /// Calculates the critical flow function of the gas.
+    ///
+    /// The critical flow function is used for critical flow venturi nozzles (ISO 9300).
+    /// With the real gas isentropic exponent κ it is approximated as
+    ///
+    /// C<sup>*</sup> = √κ·(2/(κ+1))<sup>(κ+1)/(2(κ-1))</sup>
+    ///
+    /// The limit C<sup>*</sup> = √κ·e<sup>-1/2</sup> is used when κ is close to 1.
+    ///
+    /// [`properties()`](Detail::properties) must be called first.
+    ///
+    /// ## Returns:
+    /// - c_star - Critical flow function
+    pub fn critical_flow_function(&self) -> f64 {
+        let kappa = self.kappa;
+        if (kappa - 1.0).abs() < 1.0e-6 {
+            kappa.sqrt() * (-0.5_f64).exp()
+        } else {
+            kappa.sqrt() * (2.0 / (kappa + 1.0)).powf((kappa + 1.0) / (2.0 * (kappa - 1.0)))
+        }
+    }
+
+    /// Calculates the theoretical mass flow through a choked (critical flow) nozzle.
+    ///
+    /// The current temperature and pressure is the stagnation state upstream of the nozzle,
+    /// and the density and properties are calculated there. The mass flow is
+    ///
+    /// q<sub>m</sub> = A·C<sup>*</sup>·√(P<sub>0</sub>·ρ<sub>0</sub>)
+    ///
+    /// where ρ<sub>0</sub> is the stagnation mass density and C<sup>*</sup> is the
+    /// [`critical_flow_function()`](Detail::critical_flow_function). This assumes
+    /// one-dimensional isentropic flow with a constant isentropic exponent from the stagnation
+    /// state to the throat, and that the flow is choked. The real gas behaviour is accounted for
+    /// by the stagnation density, which is √Z<sub>0</sub> different from the ideal gas value.
+    /// The discharge coefficient of a real nozzle is not included.
+    ///
+    /// ## Arguments:
+    /// - throat_area - Throat area (m²)
+    ///
+    /// ## Returns:
+    /// - q_m - Mass flow (kg/s)
+    pub fn sonic_nozzle_mass_flow(&mut self, throat_area: f64) -> Result<f64, DensityError> {
+        self.density()?;
+        self.properties();
+        let rho = self.mass_density();
+        // kPa to Pa
+        Ok(throat_area * self.critical_flow_function() * (self.p * 1000.0 * rho).sqrt())
+    }
+
+    /// Calculates the density at each of the given temperatures.
+    ///
+    /// The pressure is set once, and the density at each temperature is calculated
+    /// using the density at the previous temperature as the initial estimate.
+    /// Only the density is calculated, not the rest of the properties.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - p - Pressure (kPa)
+    /// - temperatures - Temperatures (K)
+    ///
+    /// ## Returns:
+    /// - Vec of d - Molar density (mol/l) at each temperature, or the density error
+    pub fn density_vs_temperature(
+        &mut self,
+        p: f64,
+        temperatures: &[f64],
+    ) -> Vec<Result<f64, DensityError>> {
+        let saved = self.save_state();
+
+        self.p = p;
+        self.d = 0.0;
+        let result = temperatures
+            .iter()
+            .map(|&t| {
+                self.t = t;
+                // A negative density is used as the initial estimate
+                self.d = -self.d;
+                let d = self.density().map(|_| self.d);
+                if d.is_err() {
+                    self.d = 0.0;
+                }
+                d
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the compressibility factor at each of the given temperatures.
+    ///
+    /// The pressure is set once, and the density at each temperature is calculated
+    /// using the density at the previous temperature as the initial estimate.
+    /// Only the density is calculated, not the rest of the properties.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - p - Pressure (kPa)
+    /// - temperatures - Temperatures (K)
+    ///
+    /// ## Returns:
+    /// - Vec of z - Compressibility factor at each temperature, or the density error
+    pub fn z_vs_temperature(
+        &mut self,
+        p: f64,
+        temperatures: &[f64],
+    ) -> Vec<Result<f64, DensityError>> {
+        let saved = self.save_state();
+
+        self.p = p;
+        self.d = 0.0;
+        let result = temperatures
+            .iter()
+            .map(|&t| {
+                self.t = t;
+                // A negative density is used as the initial estimate
+                self.d = -self.d;
+                let z = self.density().map(|_| self.z);
+                if z.is_err() {
+                    self.d = 0.0;
+                }
+                z
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the properties after checking that the density has been solved.
+    ///
+    /// [`properties()`](Detail::properties) uses the density `d` as it is, so if `p` or `t`
+    /// have been changed without calling [`density()`](Detail::density), the properties
+    /// are calculated at a different pressure. This function first checks that `d`
+    /// reproduces `p` within a relative tolerance of 1.0e-6. If it does not, the state is
+    /// left unchanged.
+    ///
+    /// ## Returns:
+    /// - properties - The calculated properties
+    ///
+    /// ## Error
+    /// Returns [`PropertyError::DensityNotSolved`] if `d` does not reproduce `p`.
+    pub fn properties_checked(&mut self) -> Result<PropertySet, PropertyError> {
+        let saved = self.save_state();
+        let p = self.pressure();
+        let solved = f64::abs(p - self.p) <= 1.0e-6 * self.p.abs();
+        if !solved {
+            self.restore(&saved);
+            return Err(PropertyError::DensityNotSolved);
+        }
+        let p_checked = self.p;
+        self.properties();
+        debug_assert!(f64::abs(self.p - p_checked) <= 1.0e-6 * p_checked.abs());
+        Ok(self.property_set())
+    }
+
+    /// Returns the residual entropy.
+    ///
+    /// The residual entropy is the difference between the real gas entropy and the ideal gas
+    /// entropy at the same temperature and density, s<sub>res</sub> = s - s<sub>ideal</sub>.
+    /// From the residual Helmholtz energy a<sup>r</sup> it is s<sub>res</sub> = -∂a<sup>r</sup>/∂T.
+    ///
+    /// The value is calculated by [`properties()`](Detail::properties), which must be called first.
+    ///
+    /// ## Returns:
+    /// - s_res - Residual entropy (J/(mol-K))
+    pub fn residual_entropy(&self) -> f64 {
+        self.s_res
+    }
+
+    /// Infers the molar mass of a gas from a measured mass density.
+    ///
+    /// The compressibility factor Z is calculated at `t` and `p` with the current composition,
+    /// and the molar mass is M = ρ·R·T·Z/P. This assumes that the composition, and so Z,
+    /// is known well enough, while the molar mass is what should be checked.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - p - Pressure (kPa)
+    /// - mass_density - Measured mass density (kg/m³)
+    ///
+    /// ## Returns:
+    /// - mm - Molar mass (g/mol)
+    pub fn infer_molar_mass(
+        &mut self,
+        t: f64,
+        p: f64,
+        mass_density: f64,
+    ) -> Result<f64, DensityError> {
+        let saved = self.save_state();
+
+        self.t = t;
+        self.p = p;
+        self.d = 0.0;
+        let result = self
+            .density()
+            .map(|_| mass_density * RDETAIL * t * self.z / p);
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the density and compressibility factor at line, standard and normal conditions.
+    ///
+    /// Standard conditions are 15 °C and 101.325 kPa, and normal conditions are 0 °C and 101.325 kPa.
+    /// The supercompressibility factor is relative to standard conditions, and the relative density
+    /// is relative to [dry air](Composition::DRY_AIR) at standard conditions.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - line_t - Line temperature (K)
+    /// - line_p - Line pressure (kPa)
+    pub fn metering_summary(
+        &mut self,
+        line_t: f64,
+        line_p: f64,
+    ) -> Result<MeteringSummary, DensityError> {
+        let saved = self.save_state();
+        let result = self.metering_states(line_t, line_p);
+        self.restore(&saved);
+        result
+    }
+
+    fn metering_states(
+        &mut self,
+        line_t: f64,
+        line_p: f64,
+    ) -> Result<MeteringSummary, DensityError> {
+        let standard = self.metering_state(288.15, 101.325, None)?;
+        let line = self.metering_state(line_t, line_p, Some(standard.z))?;
+        let normal = self.metering_state(273.15, 101.325, Some(standard.z))?;
+
+        let d_air = self.air_density(288.15, 101.325)?;
+        let relative_density =
+            standard.d * self.molar_mass() / (d_air * Composition::DRY_AIR.molar_mass());
+
+        Ok(MeteringSummary {
+            line,
+            standard,
+            normal,
+            relative_density,
+        })
+    }
+
+    // Calculates the density at one metering condition. Fpv is relative to `z_standard`,
+    // or 1.0 if this is the standard condition.
+    fn metering_state(
+        &mut self,
+        t: f64,
+        p: f64,
+        z_standard: Option<f64>,
+    ) -> Result<MeteringState, DensityError> {
+        self.t = t;
+        self.p = p;
+        self.d = 0.0;
+        self.density()?;
+        Ok(MeteringState {
+            t,
+            p,
+            d: self.d,
+            z: self.z,
+            fpv: z_standard.map_or(1.0, |z_standard| (z_standard / self.z).sqrt()),
+        })
+    }
+
+    /// Calculates the partial derivatives of d, z, h and w with respect to T and P
+    /// at the current temperature and pressure.
+    ///
+    /// The derivatives are central finite differences, with steps of 1.0e-5·T
+    /// and 1.0e-5·P. The density and properties are calculated at each of the four
+    /// perturbed states. The state of `self` is restored afterwards.
+    ///
+    /// ## Returns:
+    /// - jacobian - The partial derivatives. A derivative is NaN if the density calculation
+    ///   fails at one of its perturbed states.
+    pub fn output_jacobian(&mut self) -> OutputJacobian {
+        let saved = self.save_state();
+        let result = self.finite_difference_jacobian();
+        self.restore(&saved);
+        result
+    }
+
+    fn finite_difference_jacobian(&mut self) -> OutputJacobian {
+        const STEP: f64 = 1.0e-5;
+        let (t, p, d) = (self.t, self.p, self.d);
+        let (dt, dp) = (STEP * t, STEP * p);
+
+        let mut outputs = |t: f64, p: f64| self.outputs_at(t, p, d).unwrap_or([f64::NAN; 4]);
+        let t_plus = outputs(t + dt, p);
+        let t_minus = outputs(t - dt, p);
+        let p_plus = outputs(t, p + dp);
+        let p_minus = outputs(t, p - dp);
+        let dx_dt = |i: usize| (t_plus[i] - t_minus[i]) / (2.0 * dt);
+        let dx_dp = |i: usize| (p_plus[i] - p_minus[i]) / (2.0 * dp);
+
+        OutputJacobian {
+            dd_dt: dx_dt(0),
+            dd_dp: dx_dp(0),
+            dz_dt: dx_dt(1),
+            dz_dp: dx_dp(1),
+            dh_dt: dx_dt(2),
+            dh_dp: dx_dp(2),
+            dw_dt: dx_dt(3),
+            dw_dp: dx_dp(3),
+        }
+    }
+
+    // Calculates [d, z, h, w] at the given temperature and pressure,
+    // using `d_guess` as the initial estimate for the density.
+    fn outputs_at(&mut self, t: f64, p: f64, d_guess: f64) -> Result<[f64; 4], DensityError> {
+        self.t = t;
+        self.p = p;
+        self.d = -d_guess;
+        self.density()?;
+        self.properties();
+        Ok([self.d, self.z, self.h, self.w])
+    }
+
+    /// Calculates the uncertainty in molar density from the uncertainties in temperature and pressure.
+    ///
+    /// The sensitivities ∂d/∂T and ∂d/∂P are the finite differences of
+    /// [`output_jacobian()`](Detail::output_jacobian), and the uncertainties are combined
+    /// in quadrature, u(d) = √((∂d/∂T·u(T))<sup>2</sup> + (∂d/∂P·u(P))<sup>2</sup>).
+    /// The temperature and pressure uncertainties are assumed to be uncorrelated.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - sigma_t - Standard uncertainty of the temperature (K)
+    /// - sigma_p - Standard uncertainty of the pressure (kPa)
+    ///
+    /// ## Returns:
+    /// - sigma_d - Standard uncertainty of the molar density (mol/l)
+    ///
+    /// ## Error
+    /// Returns [`DensityError::IterationFail`] if the density calculation fails
+    /// at one of the perturbed states.
+    pub fn density_measurement_uncertainty(
+        &mut self,
+        sigma_t: f64,
+        sigma_p: f64,
+    ) -> Result<f64, DensityError> {
+        let jacobian = self.output_jacobian();
+        if jacobian.dd_dt.is_nan() || jacobian.dd_dp.is_nan() {
+            return Err(DensityError::IterationFail);
+        }
+        Ok((jacobian.dd_dt * sigma_t).hypot(jacobian.dd_dp * sigma_p))
+    }
+
+    /// Calculates the compressibility factor at the given temperature and density.
+    ///
+    /// The density is not iterated, so this can be used with a measured density.
+    /// The stored state is left unchanged.
+    ///
+    /// ## Returns:
+    /// - z - Compressibility factor
+    pub fn z_at_density(&mut self, t: f64, d: f64) -> f64 {
+        let saved = self.save_state();
+        self.t = t;
+        self.d = d;
+        self.pressure();
+        let z = self.z;
+        self.restore(&saved);
+        z
+    }
+
+    /// Calculates the relative pressure residual of a density at the given temperature and pressure.
+    ///
+    /// The residual is (P(T, d) - p)/p, where P(T, d) is calculated with [`pressure()`](Detail::pressure).
+    /// This can be used to verify that a density satisfies the equation of state.
+    /// The stored state is left unchanged.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - p - Pressure (kPa)
+    /// - d - Molar density (mol/l)
+    ///
+    /// ## Returns:
+    /// - residual - Relative pressure residual
+    pub fn pressure_residual(&mut self, t: f64, p: f64, d: f64) -> f64 {
+        let saved = self.save_state();
+        self.t = t;
+        self.d = d;
+        let p_calc = self.pressure();
+        self.restore(&saved);
+        (p_calc - p) / p
+    }
+
+    /// Calculates the energy flow of a volumetric flow at base conditions.
+    ///
+    /// The volumetric flow is converted to a molar flow with the real gas density
+    /// at base conditions, which includes the compressibility factor Z at base
+    /// conditions. The molar flow is then multiplied by the ideal molar
+    /// [`gross_calorific_value()`](Composition::gross_calorific_value):
+    ///
+    /// E = Q·d<sub>base</sub>·H<sub>s</sub>, where d<sub>base</sub> = P<sub>base</sub>/(Z<sub>base</sub>·R·T<sub>base</sub>)
+    ///
+    /// With Q in m³, d<sub>base</sub> in mol/l (kmol/m³) and H<sub>s</sub> in kJ/mol (MJ/kmol),
+    /// E is in MJ. A flow rate in m³/h gives an energy flow in MJ/h.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - volume_flow_std - Volumetric flow at base conditions (m³, or m³ per unit of time)
+    /// - base_t - Base temperature (K)
+    /// - base_p - Base pressure (kPa)
+    ///
+    /// ## Returns:
+    /// - e - Energy flow (MJ, or MJ per unit of time)
+    pub fn energy_flow(
+        &mut self,
+        volume_flow_std: f64,
+        base_t: f64,
+        base_p: f64,
+    ) -> Result<f64, DensityError> {
+        let saved = self.save_state();
+
+        self.t = base_t;
+        self.p = base_p;
+        self.d = 0.0;
+        let result = self.density().map(|_| {
+            volume_flow_std * self.d * heating_value::molar_gross_calorific_value(&self.x)
+        });
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Sets the molar density from a molar volume.
+    ///
+    /// d = 1/v, with v in l/mol (dm³/mol) and d in mol/l.
+    /// A molar volume of zero would give an infinite density, and is ignored.
+    ///
+    /// ## Arguments:
+    /// - v - Molar volume (l/mol)
+    pub fn set_molar_volume(&mut self, v: f64) {
+        if v != 0.0 {
+            self.d = 1.0 / v;
+        }
+    }
+
+    /// Sets the molar density from a measured mass density.
+    ///
+    /// d = ρ/M, with ρ in kg/m³ (g/l) and d in mol/l. The molar mass is recalculated
+    /// from the current composition. The density is not iterated, so
+    /// [`properties()`](Detail::properties) can be called directly afterwards
+    /// to calculate the pressure, compressibility factor and the other properties.
+    ///
+    /// ## Arguments:
+    /// - rho - Mass density (kg/m³)
+    pub fn set_mass_density(&mut self, rho: f64) {
+        self.d = rho / self.molar_mass();
+    }
+
+    /// Calculates the temperature where the pressure equals `p` at the density `d`.
+    ///
+    /// The density is held fixed and the temperature is bisected within `t_bracket`
+    /// until [`pressure()`](Detail::pressure) equals `p`.
+    /// The pressure must change sign across the bracket. The bracket can be given in either order.
+    ///
+    /// ## Arguments:
+    /// - p - Pressure (kPa)
+    /// - d - Molar density (mol/l)
+    /// - t_bracket - Lower and upper temperature of the search (K)
+    ///
+    /// ## Returns:
+    /// - t - Temperature (K). Also sets `t`, `d`, `p` and `z`.
+    ///
+    /// ## Error
+    /// Returns [`DensityError::IterationFail`] if the pressure does not change sign
+    /// across the bracket. The state of `self` is then unchanged.
+    pub fn temperature_from_pd(
+        &mut self,
+        p: f64,
+        d: f64,
+        t_bracket: (f64, f64),
+    ) -> Result<f64, DensityError> {
+        const TOLR: f64 = 1.0e-12;
+        let saved = self.save_state();
+        let (mut t_low, mut t_high) = (
+            f64::min(t_bracket.0, t_bracket.1),
+            f64::max(t_bracket.0, t_bracket.1),
+        );
+
+        self.d = d;
+        let mut residual = |t: f64| {
+            self.t = t;
+            self.pressure() - p
+        };
+        let r_low = residual(t_low);
+        if r_low * residual(t_high) > 0.0 {
+            self.restore(&saved);
+            return Err(DensityError::IterationFail);
+        }
+
+        while t_high - t_low > TOLR * t_high {
+            let t = 0.5 * (t_low + t_high);
+            if residual(t) * r_low > 0.0 {
+                t_low = t;
+            } else {
+                t_high = t;
+            }
+        }
+
+        self.t = 0.5 * (t_low + t_high);
+        self.p = self.pressure();
+        Ok(self.t)
+    }
+
+    /// Estimates the relative uncertainty of the compressibility factor.
+    ///
+    /// **Note:** This is an informational estimate, and not a calculated uncertainty.
+    ///
+    /// The estimate is a lookup of the current temperature and pressure in the
+    /// uncertainty regions of AGA Report No. 8, Part 1, Figure 1:
+    ///
+    /// | Region | Temperature (K) | Pressure (kPa) | Uncertainty |
+    /// |--------|-----------------|----------------|-------------|
+    /// | 1 | 265 - 335 | 0 - 12 000 | 0.1 % |
+    /// | 2 | 211 - 394 | 0 - 17 000 | 0.3 % |
+    /// | 3 | 144 - 450 | 0 - 70 000 | 0.5 % |
+    /// | 4 | 143 - 673 | 0 - 280 000 | 1.0 % |
+    ///
+    /// The uncertainties apply to gases in the normal [`range()`](Composition::range).
+    /// For gases in the expanded range the next wider band is used, and region 4 gives 2.0 %.
+    ///
+    /// ## Returns:
+    /// - u - Relative uncertainty of Z (-). Infinite if the state or composition is
+    ///   outside the range of the equation.
+    pub fn z_uncertainty_estimate(&self) -> f64 {
+        const BANDS: [f64; 5] = [0.001, 0.003, 0.005, 0.01, 0.02];
+        const REGIONS: [(f64, f64, f64); 4] = [
+            (265.0, 335.0, 12_000.0),
+            (211.0, 394.0, 17_000.0),
+            (144.0, 450.0, 70_000.0),
+            (143.0, 673.0, 280_000.0),
+        ];
+
+        let region = REGIONS.iter().position(|&(t_min, t_max, p_max)| {
+            (t_min..=t_max).contains(&self.t) && self.p <= p_max
+        });
+        let offset = match Composition::from_array(&self.x).range() {
+            CompositionRange::Normal => Some(0),
+            CompositionRange::Expanded => Some(1),
+            CompositionRange::OutOfRange => None,
+        };
+
+        match (region, offset) {
+            (Some(region), Some(offset)) => BANDS[region + offset],
+            _ => f64::INFINITY,
+        }
+    }
+
+    /// Calculates the second virial coefficient at the current temperature.
+    ///
+    /// B = Σ B<sub>n</sub>·T<sup>-u<sub>n</sub></sup>, where the sum is over the first 18 terms of the equation,
+    /// and B<sub>n</sub> are the composition dependent coefficients.
+    ///
+    /// ## Returns:
+    /// - b - Second virial coefficient (l/mol)
+    pub fn second_virial(&mut self) -> f64 {
+        self.x_terms();
+        (0..18).map(|n| self.bs[n] * self.t.powf(-UN[n])).sum()
+    }
+
+    /// Calculates the temperature derivative of the second virial coefficient.
+    ///
+    /// dB/dT = -Σ u<sub>n</sub>·B<sub>n</sub>·T<sup>-u<sub>n</sub>-1</sup>, see [`second_virial()`](Detail::second_virial).
+    ///
+    /// ## Returns:
+    /// - db_dt - dB/dT (l/(mol-K))
+    pub fn db_dt(&mut self) -> f64 {
+        self.x_terms();
+        -(0..18)
+            .map(|n| UN[n] * self.bs[n] * self.t.powf(-UN[n] - 1.0))
+            .sum::<f64>()
+    }
+
+    /// Calculates the density from the temperature and pressure with the virial
+    /// equation truncated after the second virial coefficient.
+    ///
+    /// Z = 1 + B·d is solved explicitly for the density, without iteration:
+    ///
+    /// d = (√(1 + 4·B·P/(R·T)) - 1)/(2·B)
+    ///
+    /// The truncation is only valid at low densities. For natural gases the
+    /// difference from [`density()`](Detail::density) is typically below 0.01 % at
+    /// atmospheric pressure, and grows to around 0.05 % at 1 MPa and 2 % at 5 MPa.
+    /// It is not valid at high pressures or near the saturation line.
+    ///
+    /// ## Returns:
+    /// Sets `d` and `z`.
+    ///
+    /// ## Error
+    /// Returns [`DensityError::IterationFail`] if the truncated equation has no solution.
+    pub fn density_virial(&mut self) -> Result<(), DensityError> {
+        let b = self.second_virial();
+        let rt = RDETAIL * self.t;
+        let disc = 1.0 + 4.0 * b * self.p / rt;
+        if disc < 0.0 {
+            return Err(DensityError::IterationFail);
+        }
+        self.d = if b == 0.0 {
+            self.p / rt
+        } else {
+            (disc.sqrt() - 1.0) / (2.0 * b)
+        };
+        self.z = 1.0 + b * self.d;
+        Ok(())
+    }
+
+    /// Runs the density solver from each of the given initial densities.
+    ///
+    /// Each guess is passed to [`density()`](Detail::density) as a negative density,
+    /// which is used as the initial estimate. A guess of zero uses the ideal gas estimate.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - p - Pressure (kPa)
+    /// - guesses - Initial densities (mol/l)
+    ///
+    /// ## Returns:
+    /// - Vec of (guess, d) - Each guess, and the converged density (mol/l) or the density error
+    pub fn convergence_basin(
+        &mut self,
+        t: f64,
+        p: f64,
+        guesses: &[f64],
+    ) -> Vec<(f64, Result<f64, DensityError>)> {
+        let saved = self.save_state();
+
+        self.t = t;
+        self.p = p;
+        let result = guesses
+            .iter()
+            .map(|&guess| {
+                self.d = -guess.abs();
+                (guess, self.density().map(|_| self.d))
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the error in the enthalpy change of a process from assuming ideal gas.
+    ///
+    /// The real gas enthalpy change is h(T<sub>2</sub>, P<sub>2</sub>) - h(T<sub>1</sub>, P<sub>1</sub>).
+    /// The ideal gas enthalpy change is ∫c<sub>p</sub><sup>0</sup>dT from T<sub>1</sub> to T<sub>2</sub>,
+    /// which is calculated exactly as h<sup>0</sup>(T<sub>2</sub>) - h<sup>0</sup>(T<sub>1</sub>) from the
+    /// ideal gas part of the equation, see [`ideal_helmholtz()`](Detail::ideal_helmholtz).
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - t1 - Inlet temperature (K)
+    /// - p1 - Inlet pressure (kPa)
+    /// - t2 - Outlet temperature (K)
+    /// - p2 - Outlet pressure (kPa)
+    ///
+    /// ## Returns:
+    /// - dh - Real gas Δh minus ideal gas Δh (J/mol)
+    pub fn enthalpy_change_ideal_error(
+        &mut self,
+        t1: f64,
+        p1: f64,
+        t2: f64,
+        p2: f64,
+    ) -> Result<f64, DensityError> {
+        let saved = self.save_state();
+
+        let result = self.enthalpies_at(t1, p1).and_then(|(h1, h01)| {
+            let (h2, h02) = self.enthalpies_at(t2, p2)?;
+            Ok((h2 - h1) - (h02 - h01))
+        });
+
+        self.restore(&saved);
+        result
+    }
+
+    // Real and ideal gas enthalpy at the given temperature and pressure.
+    fn enthalpies_at(&mut self, t: f64, p: f64) -> Result<(f64, f64), DensityError> {
+        self.t = t;
+        self.p = p;
+        self.d = 0.0;
+        self.density()?;
+        self.properties();
+        let h0 = self.a0[0] - t * self.a0[1] + RDETAIL * t;
+        Ok((self.h, h0))
+    }
+
+    /// Calculates the mean isobaric heat capacity between two temperatures.
+    ///
+    /// c<sub>p,mean</sub> = ∫c<sub>p</sub>dT/(T<sub>2</sub> - T<sub>1</sub>), integrated with the
+    /// trapezoidal rule over `steps` evenly spaced intervals at constant pressure.
+    /// The density and properties are calculated at each of the `steps + 1` temperatures.
+    /// If `t1` equals `t2`, the result is c<sub>p</sub> at that temperature.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - p - Pressure (kPa)
+    /// - t1 - Start temperature (K)
+    /// - t2 - End temperature (K)
+    /// - steps - Number of integration intervals, at least 1
+    ///
+    /// ## Returns:
+    /// - cp - Mean isobaric heat capacity (J/(mol-K))
+    pub fn mean_cp(&mut self, p: f64, t1: f64, t2: f64, steps: usize) -> Result<f64, DensityError> {
+        let steps = steps.max(1);
+        let saved = self.save_state();
+
+        self.p = p;
+        self.d = 0.0;
+        let cp: Result<Vec<f64>, DensityError> = linspace(t1, t2, steps + 1)
+            .map(|t| {
+                self.t = t;
+                // A negative density is used as the initial estimate
+                self.d = -self.d;
+                self.density()?;
+                self.properties();
+                Ok(self.cp)
+            })
+            .collect();
+
+        self.restore(&saved);
+        let cp = cp?;
+        let sum: f64 = cp[1..steps].iter().sum::<f64>() + 0.5 * (cp[0] + cp[steps]);
+        Ok(sum / steps as f64)
+    }
+
+    /// Calculates the derivative of the speed of sound with respect to temperature
+    /// at constant pressure.
+    ///
+    /// This is the `dw_dt` field of [`output_jacobian()`](Detail::output_jacobian),
+    /// a central finite difference with a temperature step of ±1.0e-5·T.
+    /// The current temperature and pressure is the state, and the state of `self`
+    /// is restored afterwards.
+    ///
+    /// ## Returns:
+    /// - dw_dt - (∂w/∂T)<sub>P</sub> (m/(s-K)), or NaN if the density calculation fails
+    pub fn dw_dt(&mut self) -> f64 {
+        self.output_jacobian().dw_dt
+    }
+
+    /// Attributes the deviation of Z from ideal mixing to the binary pairs of the mixture.
+    ///
+    /// **Note:** This is an approximate attribution, based on the second virial coefficient only.
+    ///
+    /// With ideal mixing of the pure fluid second virial coefficients, Z ≈ 1 + d·Σx<sub>i</sub>·B<sub>ii</sub>.
+    /// The difference to the second virial coefficient of the mixture can be written as a sum over the pairs,
+    ///
+    /// ΔZ<sub>ij</sub> = d·x<sub>i</sub>·x<sub>j</sub>·(2·B<sub>ij</sub> - B<sub>ii</sub> - B<sub>jj</sub>)
+    ///
+    /// where the cross virial coefficient B<sub>ij</sub> includes the binary interaction parameters
+    /// of the pair. The contributions of the third and higher virial coefficients are not attributed,
+    /// so the sum of the pair contributions is only close to the total deviation at low densities.
+    /// The current temperature and density are used.
+    ///
+    /// ## Returns:
+    /// - Vec of (a, b, dz) - The two components of each pair in the composition, and the
+    ///   contribution of the pair to Z, sorted by decreasing magnitude
+    pub fn binary_z_contributions(&mut self) -> Vec<(Component, Component, f64)> {
+        let b =
+            |bsnij2: &[f64; 18]| -> f64 { (0..18).map(|n| bsnij2[n] * self.t.powf(-UN[n])).sum() };
+
+        let mut result = Vec::new();
+        for i in (0..NC).filter(|&i| self.x[i] > 0.0) {
+            for j in (i + 1..NC).filter(|&j| self.x[j] > 0.0) {
+                let excess =
+                    2.0 * b(&self.bsnij2[i][j]) - b(&self.bsnij2[i][i]) - b(&self.bsnij2[j][j]);
+                let dz = self.d * self.x[i] * self.x[j] * excess;
+                result.push((Component::ALL[i], Component::ALL[j], dz));
+            }
+        }
+        result.sort_by(|a, b| b.2.abs().total_cmp(&a.2.abs()));
+        result
+    }
+
+    /// Calculates the relative change in density from adding a contaminant to the gas.
+    ///
+    /// `amount` mol of the contaminant is added per mol of the current composition,
+    /// and the result is normalized. The molar density is calculated for both compositions
+    /// at the same temperature and pressure.
+    /// The composition and state of `self` are restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - contaminant - The added component
+    /// - amount - Added amount (mol per mol of gas)
+    /// - t - Temperature (K)
+    /// - p - Pressure (kPa)
+    ///
+    /// ## Returns:
+    /// - dd - Relative change in molar density (d<sub>contaminated</sub> - d)/d
+    pub fn contaminant_sensitivity(
+        &mut self,
+        contaminant: Component,
+        amount: f64,
+        t: f64,
+        p: f64,
+    ) -> Result<f64, DensityError> {
+        let saved = self.save_state();
+        let x = self.x;
+
+        self.t = t;
+        self.p = p;
+        self.d = 0.0;
+        let result = self.density().and_then(|_| {
+            let d = self.d;
+            let sum: f64 = x.iter().sum::<f64>() + amount;
+            self.x[contaminant as usize] += amount;
+            self.x.iter_mut().for_each(|xi| *xi /= sum);
+            self.d = 0.0;
+            self.density()?;
+            Ok((self.d - d) / d)
+        });
+
+        self.x = x;
+        self.restore(&saved);
+        result
+    }
+}
+
+// Returns `points` evenly spaced values from `start` to `end`, both included.
+fn linspace(start: f64, end: f64, points: usize) -> impl Iterator<Item = f64> {
+    let step = if points > 1 {
+        (end - start) / (points - 1) as f64
+    } else {
+        0.0
+    };
+    (0..points).map(move |i| start + step * i as f64)
 }
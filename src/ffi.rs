@@ -82,6 +82,31 @@ pub mod detail {
         }
     }
 
+    /// Sets the composition from an array of 21 mole fractions.
+    ///
+    /// The components must be in the same order as in the `Composition` struct.
+    /// The composition is checked, and only set if it is valid.
+    ///
+    /// # Safety
+    /// `composition` must point to an array of 21 `f64` values.
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_set_composition_checked(
+        ptr: *mut Detail,
+        composition: *const f64,
+        err: *mut CompositionError,
+    ) {
+        assert!(!ptr.is_null());
+        assert!(!composition.is_null());
+        assert!(!err.is_null());
+        let aga8 = &mut *ptr;
+        let comp = Composition::from_array(&*(composition as *const [f64; 21]));
+
+        match aga8.set_composition(&comp) {
+            Ok(_) => *err = CompositionError::Ok,
+            Err(e) => *err = e,
+        }
+    }
+
     /// # Safety
     ///
     #[no_mangle]
@@ -161,6 +186,175 @@ pub mod detail {
         }
     }
 
+    /// Returns the compressibility factor.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_z(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.z
+    }
+
+    /// Returns the molar mass in g/mol.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_molar_mass(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.mm
+    }
+
+    /// Returns the first derivative of pressure with respect to density in kPa/(mol/l).
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_dp_dd(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.dp_dd
+    }
+
+    /// Returns the first derivative of pressure with respect to temperature in kPa/K.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_dp_dt(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.dp_dt
+    }
+
+    /// Returns the internal energy in J/mol.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_internal_energy(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.u
+    }
+
+    /// Returns the enthalpy in J/mol.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_enthalpy(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.h
+    }
+
+    /// Returns the entropy in J/(mol-K).
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_entropy(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.s
+    }
+
+    /// Returns the isochoric heat capacity in J/(mol-K).
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_cv(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.cv
+    }
+
+    /// Returns the isobaric heat capacity in J/(mol-K).
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_cp(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.cp
+    }
+
+    /// Returns the speed of sound in m/s.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_speed_of_sound(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.w
+    }
+
+    /// Returns the Gibbs energy in J/mol.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_gibbs_energy(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.g
+    }
+
+    /// Returns the Joule-Thomson coefficient in K/kPa.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_joule_thomson(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.jt
+    }
+
+    /// Returns the isentropic exponent.
+    ///
+    /// Call [`aga8_calculate_properties`] first.
+    ///
+    /// # Safety
+    ///
+    #[no_mangle]
+    pub unsafe extern "C" fn aga8_get_isentropic_exponent(ptr: *const Detail) -> f64 {
+        assert!(!ptr.is_null());
+        let aga8 = &*ptr;
+        aga8.kappa
+    }
+
     /// # Safety
     ///
     #[no_mangle]
@@ -0,0 +1,110 @@
+//! Flammability limits
+//!
+//! AGA8 does not define flammability limits. The functions in this module
+//! estimate the flammability limits of a gas mixture in air from the limits
+//! of the pure components.
+
+use crate::composition::{Composition, NC};
+
+// Lower and upper flammability limits in air (vol %) of the pure components,
+// in the same order as `Composition::to_array()`. Inerts have no limits.
+//
+// M. G. Zabetakis, "Flammability Characteristics of Combustible Gases and Vapors",
+// U.S. Bureau of Mines Bulletin 627 (1965).
+const LIMITS: [Option<(f64, f64)>; NC] = [
+    Some((5.0, 15.0)),  // Methane
+    None,               // Nitrogen
+    None,               // Carbon dioxide
+    Some((3.0, 12.4)),  // Ethane
+    Some((2.1, 9.5)),   // Propane
+    Some((1.8, 8.4)),   // Isobutane
+    Some((1.8, 8.4)),   // n-Butane
+    Some((1.4, 7.6)),   // Isopentane
+    Some((1.4, 7.8)),   // n-Pentane
+    Some((1.2, 7.4)),   // Hexane
+    Some((1.05, 6.7)),  // Heptane
+    Some((0.95, 6.5)),  // Octane
+    Some((0.85, 5.6)),  // Nonane
+    Some((0.75, 5.6)),  // Decane
+    Some((4.0, 75.0)),  // Hydrogen
+    None,               // Oxygen
+    Some((12.5, 74.0)), // Carbon monoxide
+    None,               // Water
+    Some((4.0, 44.0)),  // Hydrogen sulfide
+    None,               // Helium
+    None,               // Argon
+];
+
+impl Composition {
+    /// Estimates the lower and upper flammability limits of the gas in air.
+    ///
+    /// **Note:** This is an estimate, and not a part of AGA8.
+    ///
+    /// The limits of the flammable components are combined with Le Chatelier's rule,
+    /// and the inert components are treated as a diluent of the flammable part:
+    ///
+    /// L = 1 / Σ(x<sub>i</sub>/L<sub>i</sub>)
+    ///
+    /// where the sum is over the flammable components only. Inerts also narrow the
+    /// limits of the flammable part, which is not accounted for. The component limits
+    /// are from Zabetakis, U.S. Bureau of Mines Bulletin 627 (1965).
+    ///
+    /// ## Returns:
+    /// - (lfl, ufl) - Lower and upper flammability limits (vol % gas in air).
+    ///   The upper limit is capped at 100 %. A lower limit above 100 % means that the gas
+    ///   is not flammable, and it is infinite if there are no flammable components.
+    pub fn flammability_limits(&self) -> (f64, f64) {
+        let x = self.to_array();
+        let sum = self.sum();
+
+        let (inv_lfl, inv_ufl) = x
+            .iter()
+            .zip(LIMITS.iter())
+            .filter_map(|(xi, limits)| limits.map(|(lfl, ufl)| (xi / sum / lfl, xi / sum / ufl)))
+            .fold((0.0, 0.0), |acc, (l, u)| (acc.0 + l, acc.1 + u));
+
+        (1.0 / inv_lfl, f64::min(1.0 / inv_ufl, 100.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methane() {
+        let comp = Composition {
+            methane: 1.0,
+            ..Default::default()
+        };
+
+        let (lfl, ufl) = comp.flammability_limits();
+
+        assert!((lfl - 5.0).abs() < 1.0e-10);
+        assert!((ufl - 15.0).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn methane_carbon_dioxide() {
+        let comp = Composition {
+            methane: 0.8,
+            carbon_dioxide: 0.2,
+            ..Default::default()
+        };
+
+        let (lfl, ufl) = comp.flammability_limits();
+
+        assert!((lfl - 6.25).abs() < 1.0e-10);
+        assert!((ufl - 18.75).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn inert() {
+        let comp = Composition {
+            nitrogen: 1.0,
+            ..Default::default()
+        };
+
+        assert!(comp.flammability_limits().0.is_infinite());
+    }
+}
@@ -1,7 +1,8 @@
 //! The GERG2008 equation of state.
 
-use crate::composition::{Composition, CompositionError};
-use crate::DensityError;
+use crate::composition::{Component, Composition, CompositionError};
+use crate::{DensityError, PropertySet};
+use std::collections::BTreeMap;
 
 const RGERG: f64 = 8.314_472;
 pub(crate) const NC_GERG: usize = 21;
@@ -2292,6 +2293,82 @@ const GTIJ: [[f64; MAXFLDS + 1]; MAXFLDS + 1] = [
     ],
 ];
 
+/// A rough classification of a state relative to the pseudo-critical point of the mixture.
+///
+/// See [`phase_label()`](Gerg2008::phase_label).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseLabel {
+    /// Density below the pseudo-critical density, and not supercritical
+    Gas,
+    /// Density above the pseudo-critical density, at supercritical temperature and pressure
+    DensePhase,
+    /// Density above the pseudo-critical density, below the pseudo-critical temperature
+    Liquid,
+    /// Density below the pseudo-critical density, at supercritical temperature and pressure
+    Supercritical,
+}
+
+/// A warning that a state is close to the phase boundary.
+///
+/// See [`heat_capacity_with_warning()`](Gerg2008::heat_capacity_with_warning).
+/// The value is the reduced density derivative (∂P/∂d)<sub>T</sub>/(RT), which is 1.0 for an ideal gas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhaseProximityWarning {
+    /// (∂P/∂d)<sub>T</sub>/(RT) is positive, but small
+    NearPhaseBoundary(f64),
+    /// (∂P/∂d)<sub>T</sub> is zero or negative, so the state is mechanically unstable
+    Unstable(f64),
+}
+
+/// Information about the search of a failed density calculation.
+///
+/// See [`Gerg2008::last_failure`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DensityFailure {
+    /// Smallest log(v) where the pressure was evaluated, with v in l/mol
+    pub vlog_min: f64,
+    /// Largest log(v) where the pressure was evaluated, with v in l/mol
+    pub vlog_max: f64,
+    /// True if (∂P/∂d)<sub>T</sub> was negative at any of the evaluated states,
+    /// which indicates that the search entered the two-phase region
+    pub negative_dp_dd: bool,
+}
+
+/// How a binary interaction is described in the GERG2008 mixing rules.
+///
+/// See [`hydrogen_blend_support()`](Gerg2008::hydrogen_blend_support).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairModel {
+    /// Fitted reducing parameters and a fitted departure function
+    DepartureFunction,
+    /// Fitted reducing parameters, but no departure function
+    AdjustedReducing,
+    /// No fitted parameters, only the Lorentz-Berthelot combining rules
+    CombiningRules,
+}
+
+/// The GERG2008 coverage of the hydrogen binary pairs in a composition.
+///
+/// See [`hydrogen_blend_support()`](Gerg2008::hydrogen_blend_support).
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydrogenSupport {
+    /// Mole fraction of hydrogen
+    pub hydrogen: f64,
+    /// Each other component in the composition, and how its pair with hydrogen is described
+    pub pairs: Vec<(Component, PairModel)>,
+}
+
+impl HydrogenSupport {
+    /// Returns the model used for the pair of hydrogen and `component`,
+    /// or `None` if the component is not in the composition.
+    pub fn pair(&self, component: Component) -> Option<PairModel> {
+        self.pairs
+            .iter()
+            .find(|(c, _)| *c == component)
+            .map(|(_, model)| *model)
+    }
+}
+
 /// Implements the GERG2008 equation of state described in
 /// AGA Report No. 8, Part 2, First Edition, April 2017.
 ///
@@ -2394,6 +2471,11 @@ pub struct Gerg2008 {
     pub kappa: f64,
     /// Composition in mole fractions
     pub x: [f64; NC_GERG + 1],
+    /// Number of iterations used by the most recent call to [`density()`](Gerg2008::density)
+    pub last_iterations: u32,
+    /// Search information from the most recent failed call to [`density()`](Gerg2008::density),
+    /// `None` if it succeeded
+    pub last_failure: Option<DensityFailure>,
 
     drold: f64,
     trold: f64,
@@ -2423,6 +2505,14 @@ pub struct Gerg2008 {
     gtij: [[f64; MAXFLDS + 1]; MAXFLDS + 1],
 }
 
+// The state saved by `Gerg2008::save_state()` and restored by `Gerg2008::restore()`.
+struct SavedState {
+    properties: PropertySet,
+    dpddsave: f64,
+    a0: [f64; 3],
+    ar: [[f64; 4]; 4],
+}
+
 impl Gerg2008 {
     /// Creates a new instance of the Gerg2008 struct.
     pub fn new() -> Self {
@@ -4249,6 +4339,23 @@ impl Gerg2008 {
         Ok(())
     }
 
+    /// Clears the cached composition and temperature dependent terms.
+    ///
+    /// The reducing parameters are cached, and [`pressure()`](Gerg2008::pressure) and
+    /// [`properties()`](Gerg2008::properties) only recalculate them when a mole fraction
+    /// in `x` has changed by more than 1.0e-7 since the previous call. The temperature
+    /// dependent terms are likewise only recalculated when `t` changes by more than 1.0e-7 K.
+    /// Call this after changing `x` or `t` by smaller amounts, e.g. when mutating `x`
+    /// directly to reuse the instance for an unrelated composition, so that all terms are
+    /// recalculated on the next call.
+    pub fn reset_cache(&mut self) {
+        self.xold = [-1.0; NC_GERG + 1];
+        self.drold = 0.0;
+        self.trold = 0.0;
+        self.told = 0.0;
+        self.trold2 = 0.0;
+    }
+
     /// Calculates the molar mass of the current composition.
     ///
     /// # Example
@@ -4305,8 +4412,16 @@ impl Gerg2008 {
 
         let plog = self.p.ln();
         let mut vlog = -self.d.ln();
+        let mut failure = DensityFailure {
+            vlog_min: vlog,
+            vlog_max: vlog,
+            negative_dp_dd: false,
+        };
+        self.last_failure = None;
 
+        self.last_iterations = 0;
         for it in 1..=50 {
+            self.last_iterations = it;
             if !(-7.0..=100.0).contains(&vlog) || it == 20 || it == 30 || it == 40 || ifail == 1 {
                 //Current state is bad or iteration is taking too long.  Restart with completely different initial state
                 ifail = 0;
@@ -4314,6 +4429,7 @@ impl Gerg2008 {
                     // Iteration failed (above loop did not find a solution or checks made below indicate possible 2-phase state)
                     //herr = "Calculation failed to converge in GERG method, ideal gas density returned.";
                     self.d = self.p / RGERG / self.t;
+                    self.last_failure = Some(failure);
                     return Err(DensityError::IterationFail);
                 }
                 nfail += 1;
@@ -4328,6 +4444,9 @@ impl Gerg2008 {
             }
             self.d = (-vlog).exp();
             let p2 = self.pressure();
+            failure.vlog_min = failure.vlog_min.min(vlog);
+            failure.vlog_max = failure.vlog_max.max(vlog);
+            failure.negative_dp_dd |= self.dpddsave < 0.0;
             if self.dpddsave < EPSILON || p2 < EPSILON {
                 // Current state is 2-phase, try locating a different state that is single phase
                 let mut vinc = if self.d > dcx { -0.1 } else { 0.1 };
@@ -4363,6 +4482,7 @@ impl Gerg2008 {
                                 //herr = "Calculation failed to converge in GERG method, ideal gas density returned.";
                                 self.d = self.p / RGERG / self.t;
                             }
+                            self.last_failure = Some(failure);
                             return Err(DensityError::IterationFail);
                         }
                         return Ok(()); // Iteration converged
@@ -4373,6 +4493,138 @@ impl Gerg2008 {
         // Iteration failed (above loop did not find a solution or checks made below indicate possible 2-phase state)
         //herr = "Calculation failed to converge in GERG method, ideal gas density returned.";
         self.d = self.p / RGERG / self.t;
+        self.last_failure = Some(failure);
+        Err(DensityError::IterationFail)
+    }
+
+    /// Calculates the density with a bracketed Newton solver.
+    ///
+    /// This is slower than [`density()`](Gerg2008::density), but can handle states
+    /// where the Newton iteration of [`density()`](Gerg2008::density) oscillates,
+    /// e.g. compressed liquids and states close to the critical point.
+    ///
+    /// The pressure residual P(v) - p is scanned in steps of 0.1 in log(v), from ten times
+    /// the ideal gas volume down to a density of five times the pseudo-critical density,
+    /// to bracket the roots where dP/dD is positive. Each root is refined with Newton's
+    /// method on log(v), and a bisection step is taken instead if the Newton step would
+    /// leave the bracket, or if the previous step did not reduce the residual.
+    ///
+    /// Multiparameter equations of state can have spurious roots inside the two-phase region,
+    /// so only the vapor and liquid roots at each end are kept. Roots that fail the checks
+    /// for a possibly 2-phase state of [`density()`](Gerg2008::density) with `iflag` > 0
+    /// are rejected, and of the remaining roots the one with the lowest Gibbs energy is used.
+    ///
+    /// The checks calculate the properties of each root, so on success the properties are
+    /// recalculated with [`properties()`](Gerg2008::properties) at the chosen root, and
+    /// `last_iterations` is the number of iterations used to refine that root.
+    ///
+    /// The temperature `t`, pressure `p` and composition must be set before calling this method.
+    ///
+    /// ## Error
+    /// Returns [`DensityError::IterationFail`] if no root is found or the iteration
+    /// does not converge. The density is set to the ideal gas density.
+    pub fn density_robust(&mut self) -> Result<(), DensityError> {
+        const STEP: f64 = 0.1;
+        let (dcx, _tcx) = self.pseudocriticalpoint();
+        let vlog_max = (10.0 * RGERG * self.t / self.p).ln();
+        let vlog_min = -(5.0 * dcx).ln();
+
+        // Brackets on log(v), with the residual positive at the lower end
+        let mut brackets = Vec::new();
+        let mut v1 = vlog_max;
+        self.d = (-v1).exp();
+        let mut f1 = self.pressure() - self.p;
+        while v1 > vlog_min {
+            let v0 = v1 - STEP;
+            self.d = (-v0).exp();
+            let f0 = self.pressure() - self.p;
+            if f1 < 0.0 && f0 >= 0.0 {
+                brackets.push((v0, v1));
+            }
+            (v1, f1) = (v0, f0);
+        }
+        if brackets.len() > 2 {
+            brackets.drain(1..brackets.len() - 1);
+        }
+
+        let mut best: Option<(f64, f64, u32)> = None;
+        for (lo, hi) in brackets {
+            let Ok(d) = self.density_bracketed(lo, hi) else {
+                continue;
+            };
+            // Same checks for a possibly 2-phase state as in density()
+            if self.properties() <= 0.0
+                || self.dp_dd <= 0.0
+                || self.d2p_dtd <= 0.0
+                || self.cv <= 0.0
+                || self.cp <= 0.0
+                || self.w <= 0.0
+            {
+                continue;
+            }
+            let lower = match best {
+                Some((_, g, _)) => self.g < g,
+                None => true,
+            };
+            if lower {
+                best = Some((d, self.g, self.last_iterations));
+            }
+        }
+
+        match best {
+            Some((d, _, iterations)) => {
+                self.d = d;
+                self.properties();
+                self.pressure();
+                self.last_iterations = iterations;
+                Ok(())
+            }
+            None => {
+                self.d = self.p / RGERG / self.t;
+                Err(DensityError::IterationFail)
+            }
+        }
+    }
+
+    // Refines a root of the pressure residual P(v) - p between log(v) = lo and hi,
+    // where the residual is positive at lo and negative at hi.
+    fn density_bracketed(&mut self, mut lo: f64, mut hi: f64) -> Result<f64, DensityError> {
+        const TOLR: f64 = 1.0e-10;
+
+        // P(v) - p and its derivative with respect to log(v)
+        let residual = |gerg: &mut Gerg2008, vlog: f64| {
+            gerg.d = (-vlog).exp();
+            let p2 = gerg.pressure();
+            (p2 - gerg.p, -gerg.d * gerg.dpddsave)
+        };
+
+        let mut vlog = 0.5 * (lo + hi);
+        let (mut f, mut df) = residual(self, vlog);
+        let mut bisect = false;
+        for it in 1..=100 {
+            self.last_iterations = it;
+            if f > 0.0 {
+                lo = vlog;
+            } else {
+                hi = vlog;
+            }
+
+            let newton = vlog - f / df;
+            let next = if bisect || !(lo..=hi).contains(&newton) {
+                0.5 * (lo + hi)
+            } else {
+                newton
+            };
+            let step = next - vlog;
+            let f_old = f;
+            vlog = next;
+            (f, df) = residual(self, vlog);
+            bisect = f.abs() >= f_old.abs();
+
+            if step.abs() < TOLR || hi - lo < TOLR {
+                return Ok(self.d);
+            }
+        }
         Err(DensityError::IterationFail)
     }
 
@@ -4670,8 +4922,13 @@ impl Gerg2008 {
                         self.taup[i][k] = NOIK[i][k] * taup;
                     }
                 } else {
-                    for k in 1..=KPOL[i] + KEXP[i] {
-                        self.taup[i][k] = NOIK[i][k] * (self.toik[i][k] * lntau).exp();
+                    for (k, noik) in NOIK[i]
+                        .iter()
+                        .enumerate()
+                        .take(KPOL[i] + KEXP[i] + 1)
+                        .skip(1)
+                    {
+                        self.taup[i][k] = noik * (self.toik[i][k] * lntau).exp();
                     }
                 }
             }
@@ -4708,4 +4965,565 @@ impl Gerg2008 {
         }
         (dcx, tcx)
     }
+
+    /// Returns the results as a map from property name to value.
+    ///
+    /// The map is intended for logging and should be read after
+    /// [`properties()`](Gerg2008::properties) has been called.
+    pub fn to_property_map(&self) -> BTreeMap<&'static str, f64> {
+        BTreeMap::from([
+            ("temperature", self.t),
+            ("pressure", self.p),
+            ("density", self.d),
+            ("compressibility", self.z),
+            ("molar_mass", self.mm),
+            ("dp_dd", self.dp_dd),
+            ("d2p_dd2", self.d2p_dd2),
+            ("dp_dt", self.dp_dt),
+            ("internal_energy", self.u),
+            ("enthalpy", self.h),
+            ("entropy", self.s),
+            ("isochoric_heat_capacity", self.cv),
+            ("isobaric_heat_capacity", self.cp),
+            ("speed_of_sound", self.w),
+            ("gibbs_energy", self.g),
+            ("joule_thomson", self.jt),
+            ("isentropic_exponent", self.kappa),
+        ])
+    }
+
+    /// Calculates the outlet state of an isenthalpic (Joule-Thomson) expansion to the target pressure.
+    ///
+    /// The current temperature and pressure is the inlet state. The outlet temperature is found
+    /// by iterating on temperature at the target pressure until the enthalpy equals the inlet enthalpy.
+    /// The first temperature estimate uses the Joule-Thomson coefficient at the inlet.
+    ///
+    /// The sign of the Joule-Thomson coefficient gives the direction of the temperature change,
+    /// so the inlet temperature bounds the outlet temperature from one side, e.g. from above when
+    /// a gas with a positive coefficient is expanded. The temperature is bracketed with the sign
+    /// of the enthalpy error, and a Newton step on c<sub>p</sub> that would leave the bracket is
+    /// replaced by a bisection step.
+    ///
+    /// When successful, the outlet state and all of its properties are set.
+    pub fn isenthalpic_to_pressure(&mut self, target_p: f64) -> Result<(), DensityError> {
+        const TOLR: f64 = 1.0e-6;
+
+        self.density(0)?;
+        self.properties();
+        let h_in = self.h;
+        let t_in = self.t;
+
+        // The outlet temperature is below the inlet temperature if jt·Δp < 0, otherwise above
+        let (mut lo, mut hi) = if self.jt * (target_p - self.p) < 0.0 {
+            (0.0, t_in)
+        } else {
+            (t_in, f64::INFINITY)
+        };
+
+        self.t += self.jt * (target_p - self.p);
+        self.p = target_p;
+        for _ in 0..50 {
+            self.density(0)?;
+            self.properties();
+            let dh = self.h - h_in;
+            if dh.abs() < TOLR {
+                return Ok(());
+            }
+            // The enthalpy increases with temperature at constant pressure
+            if dh > 0.0 {
+                hi = self.t;
+            } else {
+                lo = self.t;
+            }
+            // (dh/dT) at constant pressure is cp
+            let t = self.t - dh / self.cp;
+            self.t = if t > lo && t < hi {
+                t
+            } else if hi.is_finite() {
+                0.5 * (lo + hi)
+            } else {
+                2.0 * lo
+            };
+        }
+        Err(DensityError::IterationFail)
+    }
+
+    /// Calculates the outlet state of an isentropic compression or expansion to the target pressure.
+    ///
+    /// The current temperature and pressure is the inlet state. The outlet temperature is found
+    /// by iterating on temperature at the target pressure until the entropy equals the inlet entropy.
+    /// The first temperature estimate uses the isentropic exponent at the inlet.
+    ///
+    /// When successful, the outlet state and all of its properties are set.
+    pub fn isentropic_to_pressure(&mut self, target_p: f64) -> Result<(), DensityError> {
+        const TOLR: f64 = 1.0e-8;
+
+        self.density(0)?;
+        self.properties();
+        let s_in = self.s;
+
+        self.t *= (target_p / self.p).powf((self.kappa - 1.0) / self.kappa);
+        self.p = target_p;
+        for _ in 0..50 {
+            self.density(0)?;
+            self.properties();
+            let ds = self.s - s_in;
+            if ds.abs() < TOLR {
+                return Ok(());
+            }
+            // (ds/dT) at constant pressure is cp/T
+            self.t -= ds * self.t / self.cp;
+        }
+        Err(DensityError::IterationFail)
+    }
+
+    /// Calculates the isentropic work of compressing the gas by a pressure ratio.
+    ///
+    /// The current temperature and pressure is the inlet state, and the outlet
+    /// state at P·ratio is found with [`isentropic_to_pressure()`](Gerg2008::isentropic_to_pressure).
+    ///
+    /// When successful, the outlet state and all of its properties are set.
+    ///
+    /// ## Arguments:
+    /// - ratio - Outlet pressure / inlet pressure
+    ///
+    /// ## Returns:
+    /// - w - Isentropic work h<sub>out</sub> - h<sub>in</sub> (J/mol)
+    pub fn compress_isentropic_ratio(&mut self, ratio: f64) -> Result<f64, DensityError> {
+        self.density(0)?;
+        self.properties();
+        let h_in = self.h;
+
+        self.isentropic_to_pressure(self.p * ratio)?;
+        Ok(self.h - h_in)
+    }
+
+    // Pressure at the pseudo-critical temperature and density.
+    // The stored state is left unchanged.
+    fn pseudocritical_pressure(&mut self) -> f64 {
+        let (dcx, tcx) = self.pseudocriticalpoint();
+        let saved = self.save_state();
+        self.t = tcx;
+        self.d = dcx;
+        let pcx = self.pressure();
+        self.restore(&saved);
+        pcx
+    }
+
+    /// Calculates the reduced temperature and pressure of the current state.
+    ///
+    /// The state is reduced by the pseudo-critical point of the mixture.
+    /// The pseudo-critical temperature and density are the mole fraction weighted averages
+    /// of the critical temperatures and critical volumes of the components.
+    /// The pseudo-critical pressure is calculated from the equation of state at that point.
+    ///
+    /// ## Returns:
+    /// - (tr, pr) - Reduced temperature T/T<sub>pc</sub> and reduced pressure P/P<sub>pc</sub>
+    pub fn reduced_state(&mut self) -> (f64, f64) {
+        let (_dcx, tcx) = self.pseudocriticalpoint();
+        let pcx = self.pseudocritical_pressure();
+        (self.t / tcx, self.p / pcx)
+    }
+
+    /// Calculates the compressibility factor at the pseudo-critical point of the mixture.
+    ///
+    /// Z<sub>c</sub> = P<sub>c</sub>·V<sub>c</sub>/(R·T<sub>c</sub>), where the pseudo-critical point
+    /// is the same as in [`reduced_state()`](Gerg2008::reduced_state).
+    pub fn critical_compressibility(&mut self) -> f64 {
+        let (dcx, tcx) = self.pseudocriticalpoint();
+        let pcx = self.pseudocritical_pressure();
+        pcx / (dcx * RGERG * tcx)
+    }
+
+    /// Returns a copy of the temperature, pressure and the calculated properties.
+    pub fn property_set(&self) -> PropertySet {
+        PropertySet {
+            t: self.t,
+            p: self.p,
+            d: self.d,
+            z: self.z,
+            mm: self.mm,
+            dp_dd: self.dp_dd,
+            d2p_dd2: self.d2p_dd2,
+            d2p_dtd: self.d2p_dtd,
+            dp_dt: self.dp_dt,
+            u: self.u,
+            h: self.h,
+            s: self.s,
+            cv: self.cv,
+            cp: self.cp,
+            w: self.w,
+            g: self.g,
+            jt: self.jt,
+            kappa: self.kappa,
+        }
+    }
+
+    /// Returns a record of the composition, temperature, pressure and the calculated properties.
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self) -> crate::ResultSnapshot {
+        crate::ResultSnapshot {
+            composition: Composition::from_array(
+                self.x[1..]
+                    .try_into()
+                    .expect("composition has NC_GERG components"),
+            ),
+            properties: self.property_set(),
+        }
+    }
+
+    // Saves the temperature, pressure, the calculated properties,
+    // and the Helmholtz energy terms that some of the outputs are calculated from.
+    fn save_state(&self) -> SavedState {
+        SavedState {
+            properties: self.property_set(),
+            dpddsave: self.dpddsave,
+            a0: self.a0,
+            ar: self.ar,
+        }
+    }
+
+    // Restores the state saved by `save_state()`.
+    fn restore(&mut self, saved: &SavedState) {
+        let state = &saved.properties;
+        self.t = state.t;
+        self.p = state.p;
+        self.d = state.d;
+        self.z = state.z;
+        self.mm = state.mm;
+        self.dp_dd = state.dp_dd;
+        self.d2p_dd2 = state.d2p_dd2;
+        self.d2p_dtd = state.d2p_dtd;
+        self.dp_dt = state.dp_dt;
+        self.u = state.u;
+        self.h = state.h;
+        self.s = state.s;
+        self.cv = state.cv;
+        self.cp = state.cp;
+        self.w = state.w;
+        self.g = state.g;
+        self.jt = state.jt;
+        self.kappa = state.kappa;
+        self.dpddsave = saved.dpddsave;
+        self.a0 = saved.a0;
+        self.ar = saved.ar;
+    }
+
+    // Finds all the mechanically stable densities (dP/dD > 0) at the current
+    // temperature and pressure, in increasing order. The density is scanned on a
+    // logarithmic grid from below the ideal gas density to five times the
+    // pseudo-critical density, and each root is refined with bisection.
+    // Multiparameter equations of state can have additional spurious roots inside
+    // the two-phase region, so only the vapor and liquid roots at each end are kept.
+    fn density_roots(&mut self) -> Vec<f64> {
+        const POINTS: usize = 400;
+        let (dcx, _tcx) = self.pseudocriticalpoint();
+        let d_min = 0.1 * self.p / (RGERG * self.t);
+        let d_max = 5.0 * dcx;
+        let step = (d_max / d_min).ln() / POINTS as f64;
+
+        let mut roots = Vec::new();
+        let mut d0 = d_min;
+        self.d = d0;
+        let mut f0 = self.pressure() - self.p;
+        for i in 1..=POINTS {
+            let d1 = d_min * (step * i as f64).exp();
+            self.d = d1;
+            let f1 = self.pressure() - self.p;
+            if f0 < 0.0 && f1 >= 0.0 {
+                let (mut lo, mut hi) = (d0, d1);
+                for _ in 0..60 {
+                    self.d = 0.5 * (lo + hi);
+                    if self.pressure() < self.p {
+                        lo = self.d;
+                    } else {
+                        hi = self.d;
+                    }
+                }
+                roots.push(0.5 * (lo + hi));
+            }
+            (d0, f0) = (d1, f1);
+        }
+        if roots.len() > 2 {
+            roots.drain(1..roots.len() - 1);
+        }
+        roots
+    }
+
+    /// Calculates the Gibbs energy of each phase at the current temperature and pressure.
+    ///
+    /// All the densities where the pressure equals `p` and dP/dD is positive are found.
+    /// In a two-phase region there is both a vapor and a liquid root, and the root with
+    /// the lowest Gibbs energy is the stable phase. The other root is metastable.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Returns:
+    /// - Vec of (d, g) - Molar density (mol/l) and Gibbs energy (J/mol) of each root,
+    ///   in order of increasing density
+    pub fn phase_gibbs_energies(&mut self) -> Vec<(f64, f64)> {
+        let saved = self.save_state();
+
+        let result = self
+            .density_roots()
+            .into_iter()
+            .map(|d| {
+                self.d = d;
+                self.properties();
+                (d, self.g)
+            })
+            .collect();
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Calculates the normal boiling point of a pure component.
+    ///
+    /// The normal boiling point is the temperature where the saturation pressure equals
+    /// 101.325 kPa. It is found with bisection on temperature, as the temperature where the
+    /// vapor and liquid roots at 101.325 kPa have the same Gibbs energy, which is equivalent
+    /// to the Maxwell equal area construction, see [`phase_gibbs_energies()`](Gerg2008::phase_gibbs_energies).
+    /// The search steps down from the critical temperature T<sub>c</sub> to 0.4·T<sub>c</sub>.
+    ///
+    /// This is only meaningful for pure components with a critical pressure above 101.325 kPa,
+    /// and a triple point pressure below it. For carbon dioxide the result is a metastable
+    /// boiling point below the sublimation temperature.
+    /// The composition and state of `self` are restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - component - The pure component
+    ///
+    /// ## Returns:
+    /// - t - Normal boiling point (K)
+    ///
+    /// ## Error
+    /// Returns [`DensityError::IterationFail`] if no phase change is found in the search range.
+    pub fn normal_boiling_point(&mut self, component: Component) -> Result<f64, DensityError> {
+        const P_NORMAL: f64 = 101.325;
+        const TOL: f64 = 1.0e-6;
+        let saved = self.save_state();
+        let x = self.x;
+        let i = component as usize + 1;
+
+        self.x = [0.0; NC_GERG + 1];
+        self.x[i] = 1.0;
+        self.p = P_NORMAL;
+        // Step down from the critical temperature, since the equation can have
+        // spurious roots far below the boiling point.
+        let t_step = |k: usize| TC[i] * (1.0 - 0.02 * k as f64);
+        let bracket = (1..=30).find(|&k| self.liquid_is_stable(t_step(k)));
+        let result = if let Some(k) = bracket {
+            let (mut t_low, mut t_high) = (t_step(k), t_step(k - 1));
+            while t_high - t_low > TOL {
+                let t = 0.5 * (t_low + t_high);
+                if self.liquid_is_stable(t) {
+                    t_low = t;
+                } else {
+                    t_high = t;
+                }
+            }
+            Ok(0.5 * (t_low + t_high))
+        } else {
+            Err(DensityError::IterationFail)
+        };
+
+        self.x = x;
+        self.restore(&saved);
+        result
+    }
+
+    // True if the liquid root has the lowest Gibbs energy at the temperature t
+    // and the current pressure. With a single root, the phase is decided by
+    // comparing its density to the pseudo-critical density.
+    fn liquid_is_stable(&mut self, t: f64) -> bool {
+        self.t = t;
+        let (dcx, _tcx) = self.pseudocriticalpoint();
+        match self.phase_gibbs_energies().as_slice() {
+            [(_, g_vapor), .., (_, g_liquid)] => g_liquid < g_vapor,
+            [(d, _)] => *d > dcx,
+            [] => false,
+        }
+    }
+
+    /// Calculates the isobaric heat capacity, and warns if the state is close to the phase boundary.
+    ///
+    /// The isobaric heat capacity grows without bound towards the critical point and the
+    /// spinodal, where (∂P/∂d)<sub>T</sub> goes to zero. The heuristic compares the reduced
+    /// derivative (∂P/∂d)<sub>T</sub>/(RT) to 0.1. Smaller values give a
+    /// [`PhaseProximityWarning::NearPhaseBoundary`], and values at or below zero give a
+    /// [`PhaseProximityWarning::Unstable`]. A state can be two-phase without a warning,
+    /// so this is not a phase equilibrium calculation.
+    ///
+    /// [`density()`](Gerg2008::density) must be called first. The properties are
+    /// calculated at the current temperature and density.
+    ///
+    /// ## Returns:
+    /// - (cp, warning) - Isobaric heat capacity (J/(mol-K)), and the warning if any
+    pub fn heat_capacity_with_warning(&mut self) -> (f64, Option<PhaseProximityWarning>) {
+        const MARGIN: f64 = 0.1;
+        self.properties();
+        let margin = self.dp_dd / (RGERG * self.t);
+        let warning = if margin <= 0.0 {
+            Some(PhaseProximityWarning::Unstable(margin))
+        } else if margin < MARGIN {
+            Some(PhaseProximityWarning::NearPhaseBoundary(margin))
+        } else {
+            None
+        };
+        (self.cp, warning)
+    }
+
+    /// Estimates the dew point temperature at the pressure `p`.
+    ///
+    /// The temperature is found with bisection as the highest temperature in the bracket
+    /// where a second, liquid like, density root exists at the given pressure,
+    /// see [`phase_gibbs_energies()`](Gerg2008::phase_gibbs_energies).
+    /// The liquid root has the same composition as the gas, so this is only an approximation
+    /// of the dew point of the mixture.
+    /// The state of `self` is restored afterwards.
+    ///
+    /// ## Arguments:
+    /// - p - Pressure (kPa)
+    /// - t_bracket - (t_low, t_high) Temperatures (K) with two density roots at t_low,
+    ///   and one density root at t_high
+    ///
+    /// ## Returns:
+    /// - t - Dew point temperature (K)
+    ///
+    /// ## Error
+    /// Returns [`DensityError::IterationFail`] if the bracket does not contain the dew point.
+    pub fn dew_point_temperature(
+        &mut self,
+        p: f64,
+        t_bracket: (f64, f64),
+    ) -> Result<f64, DensityError> {
+        const TOLR: f64 = 1.0e-4;
+        let saved = self.save_state();
+
+        self.p = p;
+        let mut two_roots = |t: f64| {
+            self.t = t;
+            self.density_roots().len() > 1
+        };
+
+        let (mut t_low, mut t_high) = t_bracket;
+        let result = if two_roots(t_low) && !two_roots(t_high) {
+            while t_high - t_low > TOLR {
+                let t = 0.5 * (t_low + t_high);
+                if two_roots(t) {
+                    t_low = t;
+                } else {
+                    t_high = t;
+                }
+            }
+            Ok(0.5 * (t_low + t_high))
+        } else {
+            Err(DensityError::IterationFail)
+        };
+
+        self.restore(&saved);
+        result
+    }
+
+    /// Classifies the current state relative to the pseudo-critical point of the mixture.
+    ///
+    /// This is a heuristic, and not a phase equilibrium calculation. The current density `d`
+    /// is compared to the pseudo-critical density d<sub>pc</sub>, and the temperature and pressure
+    /// to the pseudo-critical temperature T<sub>pc</sub> and pressure P<sub>pc</sub>,
+    /// see [`reduced_state()`](Gerg2008::reduced_state).
+    ///
+    /// | Density | T < T<sub>pc</sub> | T ≥ T<sub>pc</sub> and P ≥ P<sub>pc</sub> | Otherwise |
+    /// |---------|--------------------|-------------------------------------------|-----------|
+    /// | d > d<sub>pc</sub> | Liquid | DensePhase | Gas |
+    /// | d ≤ d<sub>pc</sub> | Gas | Supercritical | Gas |
+    ///
+    /// [`density()`](Gerg2008::density) must be called first.
+    pub fn phase_label(&mut self) -> PhaseLabel {
+        let (dcx, _tcx) = self.pseudocriticalpoint();
+        let (tr, pr) = self.reduced_state();
+        let supercritical = tr >= 1.0 && pr >= 1.0;
+
+        if self.d > dcx {
+            if tr < 1.0 {
+                PhaseLabel::Liquid
+            } else if supercritical {
+                PhaseLabel::DensePhase
+            } else {
+                PhaseLabel::Gas
+            }
+        } else if supercritical {
+            PhaseLabel::Supercritical
+        } else {
+            PhaseLabel::Gas
+        }
+    }
+
+    /// Calculates the pressure at the given temperature and mass density.
+    ///
+    /// The mass density in kg/m³ is the same as g/l, so the molar density in mol/l
+    /// is the mass density divided by the molar mass in g/mol.
+    /// The stored state is left unchanged, except for `mm` which is updated.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - mass_density - Mass density (kg/m³)
+    ///
+    /// ## Returns:
+    /// - p - Pressure (kPa)
+    pub fn pressure_from_mass_density(&mut self, t: f64, mass_density: f64) -> f64 {
+        self.molar_mass();
+        let saved = self.save_state();
+        self.t = t;
+        self.d = mass_density / self.mm;
+        let p = self.pressure();
+        self.restore(&saved);
+        p
+    }
+
+    /// Reports how the hydrogen binary pairs of the current composition are described.
+    ///
+    /// Only the methane-hydrogen pair has a fitted departure function in GERG2008.
+    /// Most other hydrogen pairs have fitted reducing parameters, and the rest
+    /// rely on the combining rules alone.
+    ///
+    /// **Note:** The hydrogen binary data used to fit GERG2008 are sparse. The stated
+    /// uncertainty of AGA8 Part 2 applies to natural gases with up to about 10 % hydrogen,
+    /// and is larger for high hydrogen fractions, especially in combination with
+    /// components that only use estimated pair parameters.
+    ///
+    /// ## Returns:
+    /// - [`HydrogenSupport`] - The hydrogen fraction, and each other component with
+    ///   x > 0 together with its [`PairModel`]. The list is empty if there is no hydrogen.
+    pub fn hydrogen_blend_support(&self) -> HydrogenSupport {
+        const H2: usize = 15;
+        let hydrogen = self.x[H2];
+        let mut pairs = Vec::new();
+
+        if hydrogen > 0.0 {
+            for j in (1..=NC_GERG).filter(|&j| j != H2 && self.x[j] > 0.0) {
+                let (i, k) = if j < H2 { (j, H2) } else { (H2, j) };
+                let model = if MNUMB[i][k] > 0 && FIJ[i][k] != 0.0 {
+                    PairModel::DepartureFunction
+                } else if self.uses_combining_rules(i, k) {
+                    PairModel::CombiningRules
+                } else {
+                    PairModel::AdjustedReducing
+                };
+                pairs.push((Component::ALL[j - 1], model));
+            }
+        }
+
+        HydrogenSupport { hydrogen, pairs }
+    }
+
+    // True if the reducing parameters of the pair i < j are the unadjusted combining rules.
+    fn uses_combining_rules(&self, i: usize, j: usize) -> bool {
+        let vc3 = |k: usize| 1.0 / DC[k].powf(1.0 / 3.0) / 2.0;
+        let gv = (vc3(i) + vc3(j)).powi(3);
+        let gt = (TC[i] * TC[j]).sqrt();
+        (self.bvij[i][j] - 1.0).abs() < 1.0e-12
+            && (self.btij[i][j] - 1.0).abs() < 1.0e-12
+            && (self.gvij[i][j] / gv - 1.0).abs() < 1.0e-12
+            && (self.gtij[i][j] / gt - 1.0).abs() < 1.0e-12
+    }
 }
@@ -0,0 +1,141 @@
+//! Heating values
+//!
+//! AGA8 does not define heating values. The functions in this module calculate
+//! the ideal gross calorific value of a gas mixture from the values of the
+//! pure components, which can be combined with the densities calculated by the
+//! equations of state to get the real gas heating value.
+
+use crate::composition::{Composition, NC};
+
+/// Ideal gas molar volume at 15 °C and 101.325 kPa in l/mol
+pub(crate) const V0: f64 = 8.314_462_618 * 288.15 / 101.325;
+
+// Ideal molar gross calorific value (kJ/mol) at a combustion reference temperature
+// of 15 °C, in the same order as `Composition::to_array()`.
+// ISO 6976 assigns water the enthalpy of condensation, inerts have no heating value.
+//
+// ISO 6976:1995, "Natural gas - Calculation of calorific values, density,
+// relative density and Wobbe index from composition", Table 3.
+const GCV: [f64; NC] = [
+    891.56,  // Methane
+    0.0,     // Nitrogen
+    0.0,     // Carbon dioxide
+    1562.14, // Ethane
+    2221.10, // Propane
+    2870.58, // Isobutane
+    2879.76, // n-Butane
+    3531.68, // Isopentane
+    3538.60, // n-Pentane
+    4198.24, // Hexane
+    4857.18, // Heptane
+    5516.01, // Octane
+    6175.82, // Nonane
+    6834.90, // Decane
+    286.15,  // Hydrogen
+    0.0,     // Oxygen
+    282.91,  // Carbon monoxide
+    44.224,  // Water
+    562.38,  // Hydrogen sulfide
+    0.0,     // Helium
+    0.0,     // Argon
+];
+
+// Mole fraction weighted gross calorific value of the mole fractions x, divided by Σx
+// so that the result is the value of the normalized composition.
+pub(crate) fn molar_gross_calorific_value(x: &[f64; NC]) -> f64 {
+    let sum: f64 = x.iter().sum();
+    x.iter().zip(GCV.iter()).map(|(xi, h)| xi * h).sum::<f64>() / sum
+}
+
+impl Composition {
+    /// Calculates the ideal molar gross calorific value of the gas.
+    ///
+    /// **Note:** This is a calculation from ISO 6976, and not a part of AGA8.
+    ///
+    /// H<sub>s</sub> = Σ x<sub>i</sub>·H<sub>s,i</sub> / Σ x<sub>i</sub>, with the component values
+    /// at a combustion reference temperature of 15 °C from ISO 6976:1995.
+    /// The division by Σ x<sub>i</sub> normalizes the composition, so a composition that
+    /// does not sum to 1.0 gives the value of the normalized composition.
+    ///
+    /// ## Returns:
+    /// - hs - Molar gross calorific value (kJ/mol)
+    pub fn gross_calorific_value(&self) -> f64 {
+        molar_gross_calorific_value(&self.to_array())
+    }
+
+    /// Calculates the ideal relative density of the gas.
+    ///
+    /// **Note:** This is a calculation from ISO 6976, and not a part of AGA8.
+    ///
+    /// d = M/M<sub>air</sub>, with the molar mass of [dry air](Composition::DRY_AIR).
+    ///
+    /// ## Returns:
+    /// - rd - Ideal relative density
+    pub fn ideal_relative_density(&self) -> f64 {
+        self.molar_mass() / Composition::DRY_AIR.molar_mass()
+    }
+
+    /// Calculates the ideal gross Wobbe index of the gas.
+    ///
+    /// **Note:** This is a calculation from ISO 6976, and not a part of AGA8.
+    ///
+    /// W<sub>s</sub> = H<sub>s</sub>/(V<sub>0</sub>·√d), where H<sub>s</sub> is the
+    /// [`gross_calorific_value()`](Composition::gross_calorific_value), V<sub>0</sub> is the
+    /// ideal gas molar volume at 15 °C and 101.325 kPa, and d is the
+    /// [`ideal_relative_density()`](Composition::ideal_relative_density).
+    ///
+    /// ## Returns:
+    /// - ws - Gross Wobbe index (MJ/m³)
+    pub fn wobbe_index(&self) -> f64 {
+        self.gross_calorific_value() / (V0 * self.ideal_relative_density().sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methane() {
+        let comp = Composition {
+            methane: 1.0,
+            ..Default::default()
+        };
+
+        assert!((comp.gross_calorific_value() - 891.56).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn inerts_dilute() {
+        let comp = Composition {
+            methane: 0.9,
+            nitrogen: 0.1,
+            ..Default::default()
+        };
+
+        assert!((comp.gross_calorific_value() - 0.9 * 891.56).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn normalized() {
+        let comp = Composition {
+            methane: 90.0,
+            nitrogen: 10.0,
+            ..Default::default()
+        };
+
+        assert!((comp.gross_calorific_value() - 0.9 * 891.56).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn methane_wobbe_index() {
+        // Ideal gross calorific value 37.706 MJ/m³ at 15 °C/15 °C in ISO 6976:1995
+        let comp = Composition {
+            methane: 1.0,
+            ..Default::default()
+        };
+
+        assert!((comp.ideal_relative_density() - 0.5539).abs() < 1.0e-3);
+        assert!((comp.wobbe_index() - 37.706 / 0.5539_f64.sqrt()).abs() < 0.01);
+    }
+}
@@ -0,0 +1,141 @@
+//! The Lee-Kesler corresponding states correlation.
+//!
+//! This is a simple, low fidelity fallback for gases that are not covered by
+//! the AGA8 DETAIL or GERG2008 equations of state. It is not part of AGA8.
+//!
+//! B. I. Lee and M. G. Kesler, "A generalized thermodynamic correlation based on
+//! three-parameter corresponding states", AIChE Journal 21 (1975) 510-527.
+
+/// Acentric factor of the reference fluid (n-octane)
+const OMEGA_R: f64 = 0.3978;
+
+// Coefficients for the simple fluid and the reference fluid
+// b1, b2, b3, b4, c1, c2, c3, c4, d1, d2, beta, gamma
+const SIMPLE: [f64; 12] = [
+    0.118_119_3,
+    0.265_728,
+    0.154_79,
+    0.030_323,
+    0.023_674_4,
+    0.018_698_4,
+    0.0,
+    0.042_724,
+    0.155_488e-4,
+    0.623_689e-4,
+    0.653_92,
+    0.060_167,
+];
+
+const REFERENCE: [f64; 12] = [
+    0.202_657_9,
+    0.331_511,
+    0.027_655,
+    0.203_488,
+    0.031_338_5,
+    0.050_361_8,
+    0.016_901,
+    0.041_577,
+    0.487_36e-4,
+    0.074_033_6e-4,
+    1.226,
+    0.037_54,
+];
+
+/// Implements the Lee-Kesler three-parameter corresponding states correlation.
+///
+/// The reduced temperature and pressure are typically calculated from
+/// pseudo-critical properties of the mixture using Kay's rule,
+/// T<sub>pc</sub> = Σ x<sub>i</sub>·T<sub>c,i</sub> and P<sub>pc</sub> = Σ x<sub>i</sub>·P<sub>c,i</sub>.
+///
+/// # Example
+/// ```
+/// use aga8::lee_kesler::LeeKesler;
+///
+/// // n-Butane at 510 K and 25 bar
+/// let z = LeeKesler::z_factor(1.2, 0.659, 0.2);
+/// assert!((z - 0.873).abs() < 2.0e-3);
+/// ```
+pub struct LeeKesler;
+
+impl LeeKesler {
+    /// Calculates the compressibility factor of the gas phase.
+    ///
+    /// ## Arguments:
+    /// - tr - Reduced temperature T/T<sub>c</sub>
+    /// - pr - Reduced pressure P/P<sub>c</sub>
+    /// - acentric - Acentric factor ω
+    ///
+    /// ## Returns:
+    /// - z - Compressibility factor
+    pub fn z_factor(tr: f64, pr: f64, acentric: f64) -> f64 {
+        let z0 = Self::fluid_z(&SIMPLE, tr, pr);
+        let zr = Self::fluid_z(&REFERENCE, tr, pr);
+        z0 + acentric / OMEGA_R * (zr - z0)
+    }
+
+    // Solves the Lee-Kesler equation for the vapor root of the given fluid.
+    fn fluid_z(c: &[f64; 12], tr: f64, pr: f64) -> f64 {
+        let b = c[0] - c[1] / tr - c[2] / tr.powi(2) - c[3] / tr.powi(3);
+        let cc = c[4] - c[5] / tr + c[6] / tr.powi(3);
+        let d = c[8] + c[9] / tr;
+        let e = c[7] / tr.powi(3);
+        let (beta, gamma) = (c[10], c[11]);
+
+        // Z(vr) - pr*vr/tr, where vr is the ideal reduced volume vr = Pc*V/(R*Tc)
+        let residual = |vr: f64| {
+            let g = gamma / vr.powi(2);
+            1.0 + b / vr
+                + cc / vr.powi(2)
+                + d / vr.powi(5)
+                + e / vr.powi(2) * (beta + g) * (-g).exp()
+                - pr * vr / tr
+        };
+
+        // Start from the ideal gas volume and iterate with the secant method
+        let mut v0 = tr / pr;
+        let mut v1 = 0.99 * v0;
+        let mut f0 = residual(v0);
+        for _ in 0..100 {
+            let f1 = residual(v1);
+            if (f1 - f0).abs() < f64::EPSILON {
+                break;
+            }
+            let v2 = v1 - f1 * (v1 - v0) / (f1 - f0);
+            (v0, f0) = (v1, f1);
+            v1 = if v2 > 0.0 { v2 } else { v1 / 2.0 };
+            if ((v1 - v0) / v1).abs() < 1.0e-12 {
+                break;
+            }
+        }
+        pr * v1 / tr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_fluid_critical_point() {
+        let z = LeeKesler::z_factor(1.0, 1.0, 0.0);
+
+        assert!((z - 0.2901).abs() < 2.0e-3);
+    }
+
+    #[test]
+    fn n_butane() {
+        // Tr = 1.2, Pr = 0.659: Z0 = 0.865, Z1 = 0.038
+        let z0 = LeeKesler::z_factor(1.2, 0.659, 0.0);
+        let z = LeeKesler::z_factor(1.2, 0.659, 0.2);
+
+        assert!((z0 - 0.865).abs() < 2.0e-3);
+        assert!((z - 0.873).abs() < 2.0e-3);
+    }
+
+    #[test]
+    fn ideal_gas_limit() {
+        let z = LeeKesler::z_factor(2.0, 1.0e-6, 0.1);
+
+        assert!((z - 1.0).abs() < 1.0e-5);
+    }
+}
@@ -63,11 +63,18 @@ assert!((1.173 - aga8_test.z).abs() < 1.0e-3);
 
 # Crate features
 * **extern** - Builds external ffi functions. These functions can be used by other programming languages.
+* **serde** - Implements `Serialize` and `Deserialize` for the composition and the calculated properties,
+  and adds [`ResultSnapshot`].
 */
 
 pub mod composition;
 pub mod detail;
+pub mod flammability;
 pub mod gerg2008;
+pub mod heating_value;
+pub mod lee_kesler;
+pub mod presets;
+pub mod transport;
 
 /// Error conditions for density calculation
 #[repr(C)]
@@ -81,5 +88,579 @@ pub enum DensityError {
     PressureTooLow,
 }
 
+/// Error conditions for property calculation
+#[derive(Debug, PartialEq, Eq)]
+pub enum PropertyError {
+    /// The density does not reproduce the pressure, call `density()` first
+    DensityNotSolved,
+}
+
+/// A set of calculated properties at one state.
+///
+/// This is a copy of the temperature, pressure and the calculated
+/// property fields of [`Detail`](detail::Detail) or [`Gerg2008`](gerg2008::Gerg2008).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PropertySet {
+    /// Temperature in K
+    pub t: f64,
+    /// Pressure in kPa
+    pub p: f64,
+    /// Molar concentration in mol/l
+    pub d: f64,
+    /// Compressibility factor
+    pub z: f64,
+    /// Molar mass in g/mol
+    pub mm: f64,
+    /// First derivative of pressure with respect
+    /// to density at constant temperature in kPa/(mol/l)
+    pub dp_dd: f64,
+    /// Second derivative of pressure with respect
+    /// to density at constant temperature in kPa/(mol/l)^2
+    pub d2p_dd2: f64,
+    /// Second derivative of pressure with respect to
+    /// temperature and density in kPa/(mol/l)/K
+    pub d2p_dtd: f64,
+    /// First derivative of pressure with respect to
+    /// temperature at constant density in kPa/K
+    pub dp_dt: f64,
+    /// Internal energy in J/mol
+    pub u: f64,
+    /// Enthalpy in J/mol
+    pub h: f64,
+    /// Entropy in J/(mol-K)
+    pub s: f64,
+    /// Isochoric heat capacity in J/(mol-K)
+    pub cv: f64,
+    /// Isobaric heat capacity in J/(mol-K)
+    pub cp: f64,
+    /// Speed of sound in m/s
+    pub w: f64,
+    /// Gibbs energy in J/mol
+    pub g: f64,
+    /// Joule-Thomson coefficient in K/kPa
+    pub jt: f64,
+    /// Isentropic Exponent
+    pub kappa: f64,
+}
+
+impl PropertySet {
+    /// Size of the binary encoding in bytes, see [`to_bytes()`](PropertySet::to_bytes).
+    pub const BYTES: usize = 18 * 8;
+
+    /// Encodes the property set as little-endian f64 values.
+    ///
+    /// The fields are encoded in the order they are declared:
+    /// t, p, d, z, mm, dp_dd, d2p_dd2, d2p_dtd, dp_dt, u, h, s, cv, cp, w, g, jt, kappa.
+    ///
+    /// ## Returns:
+    /// - bytes - The encoded fields, 8 bytes per field
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut bytes = [0; Self::BYTES];
+        for (chunk, field) in bytes.chunks_exact_mut(8).zip(self.fields()) {
+            chunk.copy_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a property set encoded with [`to_bytes()`](PropertySet::to_bytes).
+    ///
+    /// ## Panics
+    /// Panics if `bytes` is shorter than [`PropertySet::BYTES`].
+    pub fn from_bytes(bytes: &[u8]) -> PropertySet {
+        let mut fields = [0.0; 18];
+        for (field, chunk) in fields.iter_mut().zip(bytes[..Self::BYTES].chunks_exact(8)) {
+            *field = f64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        PropertySet::from_fields(fields)
+    }
+
+    /// Compares two property sets field by field with a relative tolerance.
+    ///
+    /// Each pair of fields a and b must satisfy |a - b| ≤ rel_tol·max(|a|, |b|).
+    /// The comparison is relative for all fields, so fields that are close to zero,
+    /// e.g. the enthalpy or entropy close to the reference state, must be almost equal.
+    /// NaN values are never equal.
+    ///
+    /// ## Arguments:
+    /// - other - The property set to compare with
+    /// - rel_tol - Relative tolerance
+    ///
+    /// ## Returns:
+    /// - equal - True if all fields are within the tolerance
+    pub fn approx_eq(&self, other: &PropertySet, rel_tol: f64) -> bool {
+        self.fields()
+            .iter()
+            .zip(other.fields())
+            .all(|(a, b)| (a - b).abs() <= rel_tol * a.abs().max(b.abs()))
+    }
+
+    /// Calculates the absolute difference of each field.
+    ///
+    /// ## Arguments:
+    /// - other - The property set to compare with
+    ///
+    /// ## Returns:
+    /// - diff - A property set where each field is |self - other|
+    pub fn diff(&self, other: &PropertySet) -> PropertySet {
+        let mut fields = self.fields();
+        for (a, b) in fields.iter_mut().zip(other.fields()) {
+            *a = (*a - b).abs();
+        }
+        PropertySet::from_fields(fields)
+    }
+
+    // The fields in the order they are declared
+    fn fields(&self) -> [f64; 18] {
+        [
+            self.t,
+            self.p,
+            self.d,
+            self.z,
+            self.mm,
+            self.dp_dd,
+            self.d2p_dd2,
+            self.d2p_dtd,
+            self.dp_dt,
+            self.u,
+            self.h,
+            self.s,
+            self.cv,
+            self.cp,
+            self.w,
+            self.g,
+            self.jt,
+            self.kappa,
+        ]
+    }
+
+    fn from_fields(f: [f64; 18]) -> PropertySet {
+        PropertySet {
+            t: f[0],
+            p: f[1],
+            d: f[2],
+            z: f[3],
+            mm: f[4],
+            dp_dd: f[5],
+            d2p_dd2: f[6],
+            d2p_dtd: f[7],
+            dp_dt: f[8],
+            u: f[9],
+            h: f[10],
+            s: f[11],
+            cv: f[12],
+            cp: f[13],
+            w: f[14],
+            g: f[15],
+            jt: f[16],
+            kappa: f[17],
+        }
+    }
+}
+
+/// Thermodynamic properties per unit mass.
+///
+/// The molar properties are converted with J/kg = J/mol ÷ (g/mol) × 1000.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SpecificProperties {
+    /// Internal energy in J/kg
+    pub u: f64,
+    /// Enthalpy in J/kg
+    pub h: f64,
+    /// Entropy in J/(kg-K)
+    pub s: f64,
+    /// Isochoric heat capacity in J/(kg-K)
+    pub cv: f64,
+    /// Isobaric heat capacity in J/(kg-K)
+    pub cp: f64,
+    /// Gibbs energy in J/kg
+    pub g: f64,
+}
+
+/// A record of the inputs and outputs of a calculation.
+///
+/// Created with [`Detail::snapshot()`](detail::Detail::snapshot)
+/// or [`Gerg2008::snapshot()`](gerg2008::Gerg2008::snapshot).
+#[cfg(feature = "serde")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResultSnapshot {
+    /// Composition in mole fractions
+    pub composition: composition::Composition,
+    /// Temperature, pressure and the calculated properties
+    pub properties: PropertySet,
+}
+
+/// The equations of state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// The GERG2008 equation of state
+    Gerg,
+    /// The AGA8 DETAIL equation of state
+    Detail,
+}
+
+/// Calculates density and properties with GERG2008, and falls back to DETAIL
+/// if the GERG2008 density calculation fails to iterate.
+///
+/// The composition is set with `set_composition()`, so it must pass
+/// [`Composition::check()`](composition::Composition::check).
+///
+/// ## Returns:
+/// - (properties, model) - The calculated properties, and the model that calculated them
+///
+/// ## Error
+/// Returns the density error of DETAIL if both models fail. A composition that does not pass
+/// the check is reported as [`DensityError::IterationFail`].
+///
+/// # Example
+/// ```
+/// use aga8::composition::Composition;
+/// use aga8::{solve_with_fallback, Model};
+///
+/// let comp = Composition {
+///     methane: 0.9,
+///     ethane: 0.1,
+///     ..Default::default()
+/// };
+///
+/// let (props, model) = solve_with_fallback(&comp, 300.0, 5_000.0).unwrap();
+/// assert_eq!(model, Model::Gerg);
+/// assert!(props.z < 1.0);
+/// ```
+pub fn solve_with_fallback(
+    comp: &composition::Composition,
+    t: f64,
+    p: f64,
+) -> Result<(PropertySet, Model), DensityError> {
+    let mut gerg = gerg2008::Gerg2008::new();
+    gerg.set_composition(comp)
+        .map_err(|_| DensityError::IterationFail)?;
+    gerg.t = t;
+    gerg.p = p;
+    match gerg.density(0) {
+        Ok(()) => {
+            gerg.properties();
+            return Ok((gerg.property_set(), Model::Gerg));
+        }
+        Err(DensityError::IterationFail) => {}
+        Err(e) => return Err(e),
+    }
+
+    let mut detail = detail::Detail::new();
+    detail
+        .set_composition(comp)
+        .map_err(|_| DensityError::IterationFail)?;
+    detail.t = t;
+    detail.p = p;
+    detail.density()?;
+    detail.properties();
+    Ok((detail.property_set(), Model::Detail))
+}
+
+/// Calculates density and properties with DETAIL for a composition given in mass fractions.
+///
+/// The mass fractions are converted to normalized mole fractions with
+/// [`Composition::from_mass_fractions()`](composition::Composition::from_mass_fractions).
+///
+/// ## Arguments:
+/// - w - Mass fractions, in the order of [`Composition::to_array()`](composition::Composition::to_array)
+/// - t - Temperature (K)
+/// - p - Pressure (kPa)
+///
+/// ## Returns:
+/// - properties - The calculated properties
+///
+/// ## Error
+/// Returns [`DensityError::IterationFail`] if the density iteration fails. A composition that
+/// does not pass [`Composition::check()`](composition::Composition::check), for example when
+/// all the mass fractions are zero or one is negative, is also reported as
+/// [`DensityError::IterationFail`], as in the other functions that set a composition.
+pub fn solve_from_mass_fractions(
+    w: [f64; composition::NC],
+    t: f64,
+    p: f64,
+) -> Result<PropertySet, DensityError> {
+    let comp = composition::Composition::from_mass_fractions(&w);
+
+    let mut detail = detail::Detail::new();
+    detail
+        .set_composition(&comp)
+        .map_err(|_| DensityError::IterationFail)?;
+    detail.t = t;
+    detail.p = p;
+    detail.density()?;
+    detail.properties();
+    Ok(detail.property_set())
+}
+
+/// Relative differences between GERG2008 and DETAIL at the same state.
+///
+/// Each field is (GERG2008 - DETAIL) / DETAIL.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ModelComparison {
+    /// Relative difference in molar density
+    pub d: f64,
+    /// Relative difference in compressibility factor
+    pub z: f64,
+    /// Relative difference in isobaric heat capacity
+    pub cp: f64,
+    /// Relative difference in isochoric heat capacity
+    pub cv: f64,
+    /// Relative difference in speed of sound
+    pub w: f64,
+}
+
+/// Calculates density and properties with both GERG2008 and DETAIL,
+/// and compares the results.
+///
+/// The composition is set with `set_composition()`, so it must pass
+/// [`Composition::check()`](composition::Composition::check).
+///
+/// ## Returns:
+/// - comparison - The relative differences between the models
+///
+/// ## Error
+/// Returns the density error if either model fails. A composition that does not pass
+/// the check is reported as [`DensityError::IterationFail`].
+pub fn compare_models(
+    comp: &composition::Composition,
+    t: f64,
+    p: f64,
+) -> Result<ModelComparison, DensityError> {
+    let (mut gerg, mut detail) = solve_both(comp, t, p)?;
+    gerg.properties();
+    detail.properties();
+
+    let rel = |g: f64, d: f64| (g - d) / d;
+    Ok(ModelComparison {
+        d: rel(gerg.d, detail.d),
+        z: rel(gerg.z, detail.z),
+        cp: rel(gerg.cp, detail.cp),
+        cv: rel(gerg.cv, detail.cv),
+        w: rel(gerg.w, detail.w),
+    })
+}
+
+/// Calculates the relative difference in speed of sound between GERG2008 and DETAIL.
+///
+/// The difference is (w<sub>GERG</sub> - w<sub>DETAIL</sub>)/w<sub>DETAIL</sub>.
+/// The composition is set with `set_composition()`, as in [`compare_models()`].
+///
+/// ## Returns:
+/// - dw - Relative difference in speed of sound
+///
+/// ## Error
+/// Returns the density error if either model fails. A composition that does not pass
+/// [`Composition::check()`](composition::Composition::check) is reported as
+/// [`DensityError::IterationFail`].
+pub fn sos_model_difference(
+    comp: &composition::Composition,
+    t: f64,
+    p: f64,
+) -> Result<f64, DensityError> {
+    let (mut gerg, mut detail) = solve_both(comp, t, p)?;
+    gerg.properties();
+    detail.properties();
+
+    Ok((gerg.w - detail.w) / detail.w)
+}
+
+/// Calculates the density with both GERG2008 and DETAIL, and finds the model
+/// that is closest to a measured density.
+///
+/// The composition is set with `set_composition()`, so it must pass
+/// [`Composition::check()`](composition::Composition::check).
+///
+/// ## Arguments:
+/// - comp - Composition
+/// - t - Temperature (K)
+/// - p - Pressure (kPa)
+/// - measured_d - Measured molar density (mol/l)
+///
+/// ## Returns:
+/// - (model, error) - The closest model, and its relative error (d - measured_d) / measured_d
+///
+/// ## Error
+/// Returns the density error if either model fails. A composition that does not pass
+/// the check is reported as [`DensityError::IterationFail`].
+pub fn best_density_match(
+    comp: &composition::Composition,
+    t: f64,
+    p: f64,
+    measured_d: f64,
+) -> Result<(Model, f64), DensityError> {
+    let (gerg, detail) = solve_both(comp, t, p)?;
+    let gerg_error = (gerg.d - measured_d) / measured_d;
+    let detail_error = (detail.d - measured_d) / measured_d;
+
+    if gerg_error.abs() <= detail_error.abs() {
+        Ok((Model::Gerg, gerg_error))
+    } else {
+        Ok((Model::Detail, detail_error))
+    }
+}
+
+// Calculates the density with both GERG2008 and DETAIL.
+fn solve_both(
+    comp: &composition::Composition,
+    t: f64,
+    p: f64,
+) -> Result<(gerg2008::Gerg2008, detail::Detail), DensityError> {
+    let mut gerg = gerg2008::Gerg2008::new();
+    gerg.set_composition(comp)
+        .map_err(|_| DensityError::IterationFail)?;
+    gerg.t = t;
+    gerg.p = p;
+    gerg.density(0)?;
+
+    let mut detail = detail::Detail::new();
+    detail
+        .set_composition(comp)
+        .map_err(|_| DensityError::IterationFail)?;
+    detail.t = t;
+    detail.p = p;
+    detail.density()?;
+
+    Ok((gerg, detail))
+}
+
+/// Calculates the properties of blends of a base gas and one added component.
+///
+/// For each fraction f, the base gas is normalized and mixed with the added component as
+/// (1 - f)·base + f·add, and the density and properties are calculated with DETAIL.
+///
+/// ## Arguments:
+/// - base - Base gas composition
+/// - add - The component that is added to the base gas
+/// - fractions - Mole fractions of the added component in the blend
+/// - t - Temperature (K)
+/// - p - Pressure (kPa)
+///
+/// ## Returns:
+/// - Vec of properties - The properties of each blend, or the density error
+///
+/// Each blend is set with `set_composition()`. A blend that does not pass
+/// [`Composition::check()`](composition::Composition::check), for example with an empty
+/// base gas or a fraction outside 0 - 1, is reported as [`DensityError::IterationFail`].
+pub fn blend_sweep(
+    base: &composition::Composition,
+    add: composition::Component,
+    fractions: &[f64],
+    t: f64,
+    p: f64,
+) -> Vec<Result<PropertySet, DensityError>> {
+    let base = base.to_array();
+    let sum: f64 = base.iter().sum();
+
+    let mut detail = detail::Detail::new();
+    detail.t = t;
+    detail.p = p;
+    fractions
+        .iter()
+        .map(|&f| {
+            let mut x = base.map(|x| x / sum * (1.0 - f));
+            x[add as usize] += f;
+            detail
+                .set_composition(&composition::Composition::from_array(&x))
+                .map_err(|_| DensityError::IterationFail)?;
+            detail.d = 0.0;
+            detail.density()?;
+            detail.properties();
+            Ok(detail.property_set())
+        })
+        .collect()
+}
+
+/// Creates a composition with the given Wobbe index and relative density from a base gas.
+///
+/// The mole fractions of `adjust` and of methane, which is used as the balance component,
+/// are solved for. The other components keep the same proportions as in `base`.
+/// With the ideal definitions of [`wobbe_index()`](composition::Composition::wobbe_index) and
+/// [`ideal_relative_density()`](composition::Composition::ideal_relative_density), the targets
+/// give the molar mass and the gross calorific value of the gas, which are both linear in the
+/// mole fractions. This gives a 2x2 linear system that is solved directly.
+///
+/// ## Arguments:
+/// - wobbe - Target gross Wobbe index (MJ/m³)
+/// - relative_density - Target ideal relative density
+/// - base - The base gas
+/// - adjust - The component to adjust, must not be methane
+///
+/// ## Returns:
+/// - comp - Normalized composition with the target Wobbe index and relative density
+///
+/// ## Error
+/// Returns [`CompositionError::Empty`](composition::CompositionError::Empty) if `base` is empty,
+/// or [`CompositionError::OutOfRange`](composition::CompositionError::OutOfRange) if the
+/// targets can not be reached with non-negative mole fractions.
+pub fn composition_from_wobbe_rd(
+    wobbe: f64,
+    relative_density: f64,
+    base: &composition::Composition,
+    adjust: composition::Component,
+) -> Result<composition::Composition, composition::CompositionError> {
+    use composition::{Component, Composition, CompositionError};
+
+    let (i_adj, i_ch4) = (adjust as usize, Component::Methane as usize);
+    if adjust == Component::Methane {
+        return Err(CompositionError::OutOfRange);
+    }
+    if base.sum() == 0.0 {
+        return Err(CompositionError::Empty);
+    }
+
+    // Pure component molar mass and calorific value
+    let pure = |i: usize| {
+        let mut x = [0.0; composition::NC];
+        x[i] = 1.0;
+        let comp = Composition::from_array(&x);
+        (comp.molar_mass(), comp.gross_calorific_value())
+    };
+
+    // The rest of the base gas, normalized
+    let mut rest = base.to_array();
+    rest[i_adj] = 0.0;
+    rest[i_ch4] = 0.0;
+    let rest_sum: f64 = rest.iter().sum();
+    if rest_sum > 0.0 {
+        rest.iter_mut().for_each(|x| *x /= rest_sum);
+    }
+    let rest_comp = Composition::from_array(&rest);
+    let (mm_r, hs_r) = if rest_sum > 0.0 {
+        (rest_comp.molar_mass(), rest_comp.gross_calorific_value())
+    } else {
+        (0.0, 0.0)
+    };
+    let (mm_a, hs_a) = pure(i_adj);
+    let (mm_m, hs_m) = pure(i_ch4);
+
+    // Targets
+    let mm = relative_density * Composition::DRY_AIR.molar_mass();
+    let hs = wobbe * heating_value::V0 * relative_density.sqrt();
+
+    // x_a·(M_a - M_r) + x_m·(M_m - M_r) = M - M_r, and the same for Hs
+    let (a11, a12, b1) = (mm_a - mm_r, mm_m - mm_r, mm - mm_r);
+    let (a21, a22, b2) = (hs_a - hs_r, hs_m - hs_r, hs - hs_r);
+    let det = a11 * a22 - a12 * a21;
+    if det.abs() < f64::EPSILON {
+        return Err(CompositionError::OutOfRange);
+    }
+    let x_a = (b1 * a22 - a12 * b2) / det;
+    let x_m = (a11 * b2 - b1 * a21) / det;
+    // The rest is rounding noise when the targets are reached with x_a and x_m alone
+    let x_r = match 1.0 - x_a - x_m {
+        x_r if x_r.abs() < 1.0e-12 => 0.0,
+        x_r => x_r,
+    };
+    if x_a < 0.0 || x_m < 0.0 || x_r < 0.0 || (rest_sum == 0.0 && x_r > 0.0) {
+        return Err(CompositionError::OutOfRange);
+    }
+
+    let mut x = rest.map(|x| x * x_r);
+    x[i_adj] = x_a;
+    x[i_ch4] = x_m;
+    Ok(Composition::from_array(&x))
+}
+
 #[cfg(feature = "extern")]
 pub mod ffi;
@@ -0,0 +1,179 @@
+//! Reference gas compositions
+//!
+//! The example gases of AGA Report No. 8 (1994), which are also used as test gases
+//! in ISO 12213-2. They can be used to compare results with the published examples.
+
+use crate::composition::Composition;
+
+const EMPTY: Composition = Composition {
+    methane: 0.0,
+    nitrogen: 0.0,
+    carbon_dioxide: 0.0,
+    ethane: 0.0,
+    propane: 0.0,
+    isobutane: 0.0,
+    n_butane: 0.0,
+    isopentane: 0.0,
+    n_pentane: 0.0,
+    hexane: 0.0,
+    heptane: 0.0,
+    octane: 0.0,
+    nonane: 0.0,
+    decane: 0.0,
+    hydrogen: 0.0,
+    oxygen: 0.0,
+    carbon_monoxide: 0.0,
+    water: 0.0,
+    hydrogen_sulfide: 0.0,
+    helium: 0.0,
+    argon: 0.0,
+};
+
+const NAMES: [&str; 5] = [
+    "Gulf Coast",
+    "Amarillo",
+    "Ekofisk",
+    "High N2",
+    "High CO2-N2",
+];
+
+const PRESETS: [Composition; 5] = [
+    // Gulf Coast
+    Composition {
+        methane: 0.965_222,
+        nitrogen: 0.002_595,
+        carbon_dioxide: 0.005_956,
+        ethane: 0.018_186,
+        propane: 0.004_596,
+        isobutane: 0.000_977,
+        n_butane: 0.001_007,
+        isopentane: 0.000_473,
+        n_pentane: 0.000_324,
+        hexane: 0.000_664,
+        ..EMPTY
+    },
+    // Amarillo
+    Composition {
+        methane: 0.906_724,
+        nitrogen: 0.031_284,
+        carbon_dioxide: 0.004_676,
+        ethane: 0.045_279,
+        propane: 0.008_280,
+        isobutane: 0.001_037,
+        n_butane: 0.001_563,
+        isopentane: 0.000_321,
+        n_pentane: 0.000_443,
+        hexane: 0.000_393,
+        ..EMPTY
+    },
+    // Ekofisk
+    Composition {
+        methane: 0.859_063,
+        nitrogen: 0.010_068,
+        carbon_dioxide: 0.014_954,
+        ethane: 0.084_919,
+        propane: 0.023_015,
+        isobutane: 0.003_486,
+        n_butane: 0.003_506,
+        isopentane: 0.000_509,
+        n_pentane: 0.000_480,
+        ..EMPTY
+    },
+    // High N2
+    Composition {
+        methane: 0.814_41,
+        nitrogen: 0.134_65,
+        carbon_dioxide: 0.009_85,
+        ethane: 0.033,
+        propane: 0.006_05,
+        isobutane: 0.001,
+        n_butane: 0.001_04,
+        ..EMPTY
+    },
+    // High CO2-N2
+    Composition {
+        methane: 0.812_12,
+        nitrogen: 0.057_02,
+        carbon_dioxide: 0.075_85,
+        ethane: 0.043_03,
+        propane: 0.008_95,
+        isobutane: 0.001_51,
+        n_butane: 0.001_52,
+        ..EMPTY
+    },
+];
+
+/// Returns the names of the reference gases, see [`Composition::preset()`].
+pub fn preset_names() -> &'static [&'static str] {
+    &NAMES
+}
+
+impl Composition {
+    /// Returns a reference gas composition by name.
+    ///
+    /// The names are matched without regard to case.
+    ///
+    /// | Name | Molar mass (g/mol) |
+    /// |------|--------------------|
+    /// | Gulf Coast | 16.7994 |
+    /// | Amarillo | 17.5955 |
+    /// | Ekofisk | 18.7683 |
+    /// | High N2 | 18.6488 |
+    /// | High CO2-N2 | 19.8290 |
+    ///
+    /// The molar masses follow from the published mole percentages and the component
+    /// molar masses of AGA Report No. 8 (1994).
+    ///
+    /// # Example
+    /// ```
+    /// use aga8::composition::Composition;
+    ///
+    /// let comp = Composition::preset("Ekofisk").unwrap();
+    ///
+    /// assert!((comp.sum() - 1.0).abs() < 1.0e-10);
+    /// ```
+    pub fn preset(name: &str) -> Option<Composition> {
+        NAMES
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(name))
+            .map(|i| PRESETS[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gulf_coast_molar_mass() {
+        let comp = Composition::preset("gulf coast").unwrap();
+
+        // Mole percent of the Gulf Coast gas in AGA Report No. 8 (1994), and the component
+        // molar masses (g/mol) of the same report, in the order of `Composition::to_array()`
+        let published = [
+            (96.5222, 16.043),
+            (0.2595, 28.0135),
+            (0.5956, 44.01),
+            (1.8186, 30.07),
+            (0.4596, 44.097),
+            (0.0977, 58.123),
+            (0.1007, 58.123),
+            (0.0473, 72.15),
+            (0.0324, 72.15),
+            (0.0664, 86.177),
+        ];
+        let mm: f64 = published.iter().map(|(x, m)| x / 100.0 * m).sum();
+
+        assert!((mm - 16.7994).abs() < 1.0e-4);
+        assert!((comp.molar_mass() - mm).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn presets_are_normalized() {
+        for name in preset_names() {
+            let comp = Composition::preset(name).unwrap();
+            assert!((comp.sum() - 1.0).abs() < 1.0e-10);
+        }
+        assert_eq!(Composition::preset("Unknown"), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! Transport properties
+//!
+//! AGA8 does not define any transport properties. The functions in this module
+//! are empirical correlations that can be combined with the densities calculated
+//! by the equations of state to get an estimate of the transport properties.
+
+use crate::composition::Composition;
+
+/// Molar gas constant in J/(mol-K)
+const R: f64 = 8.314_462_618;
+
+impl Composition {
+    /// Estimates the dynamic viscosity of the gas with the Lee-Gonzalez-Eakin correlation.
+    ///
+    /// **Note:** This is an empirical correlation for natural gases, and not a part of AGA8.
+    ///
+    /// A. L. Lee, M. H. Gonzalez and B. E. Eakin, "The Viscosity of Natural Gases",
+    /// Journal of Petroleum Technology 18 (1966) 997-1000.
+    ///
+    /// μ = 10<sup>-4</sup>·K·exp(X·ρ<sup>Y</sup>), where
+    /// - K = (9.4 + 0.02·M)·T<sup>1.5</sup>/(209 + 19·M + T)
+    /// - X = 3.5 + 986/T + 0.01·M
+    /// - Y = 2.4 - 0.2·X
+    ///
+    /// with T in °R, ρ in g/cm³ and μ in cP.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - molar_density - Molar density (mol/l), e.g. from [`Detail::density()`](crate::detail::Detail::density)
+    ///
+    /// ## Returns:
+    /// - mu - Dynamic viscosity (μPa·s)
+    pub fn viscosity(&self, t: f64, molar_density: f64) -> f64 {
+        let mm = self.molar_mass();
+        let t_r = t * 1.8;
+        let rho = molar_density * mm / 1000.0;
+
+        let k = (9.4 + 0.02 * mm) * t_r.powf(1.5) / (209.0 + 19.0 * mm + t_r);
+        let x = 3.5 + 986.0 / t_r + 0.01 * mm;
+        let y = 2.4 - 0.2 * x;
+
+        // cP to μPa·s
+        1.0e-4 * k * (x * rho.powf(y)).exp() * 1000.0
+    }
+
+    /// Calculates the mean speed of the gas molecules from kinetic theory.
+    ///
+    /// **Note:** This is an ideal gas kinetic theory approximation, and not a part of AGA8.
+    ///
+    /// v = √(8·R·T/(π·M))
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    ///
+    /// ## Returns:
+    /// - v - Mean molecular speed (m/s)
+    pub fn mean_molecular_speed(&self, t: f64) -> f64 {
+        let mm = self.molar_mass() / 1000.0;
+        (8.0 * R * t / (std::f64::consts::PI * mm)).sqrt()
+    }
+
+    /// Estimates the mean free path of the gas molecules from the viscosity.
+    ///
+    /// **Note:** This is an ideal gas kinetic theory approximation, and not a part of AGA8.
+    ///
+    /// λ = μ/ρ·√(π·M/(2·R·T)), where μ is the [`viscosity()`](Composition::viscosity)
+    /// and ρ is the mass density.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - molar_density - Molar density (mol/l)
+    ///
+    /// ## Returns:
+    /// - lambda - Mean free path (m)
+    pub fn mean_free_path(&self, t: f64, molar_density: f64) -> f64 {
+        let mm = self.molar_mass() / 1000.0;
+        let mu = self.viscosity(t, molar_density) * 1.0e-6;
+        let rho = molar_density * mm * 1000.0;
+        mu / rho * (std::f64::consts::PI * mm / (2.0 * R * t)).sqrt()
+    }
+
+    /// Estimates the thermal diffusivity of the gas.
+    ///
+    /// **Note:** The thermal conductivity is an empirical estimate, and not a part of AGA8.
+    ///
+    /// α = k/(ρ·c<sub>p</sub>), where the thermal conductivity k is estimated with the
+    /// modified Eucken correlation (B. E. Poling, J. M. Prausnitz and J. P. O'Connell,
+    /// "The Properties of Gases and Liquids", 5th ed., McGraw-Hill, 2001),
+    /// k = μ·(1.32·c<sub>v</sub> + 1.77·R/M), with c<sub>v</sub> = c<sub>p</sub> - R/M
+    /// and μ from [`viscosity()`](Composition::viscosity).
+    /// The correlation is for dilute gases, and underestimates the conductivity at high pressure.
+    ///
+    /// ## Arguments:
+    /// - t - Temperature (K)
+    /// - mass_density - Mass density (kg/m³)
+    /// - cp_mass - Isobaric heat capacity (J/(kg·K))
+    ///
+    /// ## Returns:
+    /// - alpha - Thermal diffusivity (m²/s)
+    pub fn thermal_diffusivity(&self, t: f64, mass_density: f64, cp_mass: f64) -> f64 {
+        let mm = self.molar_mass();
+        let mu = self.viscosity(t, mass_density / mm) * 1.0e-6;
+        let r_mass = R / mm * 1000.0;
+        let k = mu * (1.32 * (cp_mass - r_mass) + 1.77 * r_mass);
+        k / (mass_density * cp_mass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lge_viscosity() {
+        // Gas with molar mass 20.85 g/mol at 600 °R and 8.3 lb/ft³
+        // has a viscosity of 0.0173 cP (Ahmed, Reservoir Engineering Handbook).
+        let x = (30.07 - 20.85) / (30.07 - 16.043);
+        let comp = Composition {
+            methane: x,
+            ethane: 1.0 - x,
+            ..Default::default()
+        };
+        let t = 600.0 / 1.8;
+        let molar_density = 8.3 * 16.018_46 / comp.molar_mass();
+
+        let mu = comp.viscosity(t, molar_density);
+
+        assert!((mu - 17.3).abs() < 0.1);
+    }
+
+    #[test]
+    fn methane_kinetic_theory() {
+        let comp = Composition {
+            methane: 1.0,
+            ..Default::default()
+        };
+        let molar_density = 101.325 / (R * 300.0);
+
+        let v = comp.mean_molecular_speed(300.0);
+        let lambda = comp.mean_free_path(300.0, molar_density);
+
+        assert!((v - 629.2).abs() < 0.1);
+        assert!(lambda > 4.0e-8 && lambda < 7.0e-8);
+    }
+
+    #[test]
+    fn methane_thermal_diffusivity() {
+        // Methane at 300 K and 101.325 kPa: k = 0.0343 W/(m·K), cp = 2230 J/(kg·K)
+        // and rho = 0.651 kg/m³, which gives a thermal diffusivity of 2.36e-5 m²/s.
+        let comp = Composition {
+            methane: 1.0,
+            ..Default::default()
+        };
+
+        let alpha = comp.thermal_diffusivity(300.0, 0.651, 2230.0);
+
+        assert!(alpha > 0.0);
+        assert!((alpha / 2.36e-5 - 1.0).abs() < 0.1);
+    }
+}
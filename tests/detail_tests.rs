@@ -1,5 +1,5 @@
-use aga8::composition::Composition;
-use aga8::detail::Detail;
+use aga8::composition::{Component, Composition};
+use aga8::detail::{BinaryParamKind, BinaryParams, ComputedFields, Detail};
 
 const COMP_FULL: Composition = Composition {
     methane: 0.778_24,
@@ -126,3 +126,1369 @@ fn detail_api_calculate_molar_mass() {
         aga8_free(d_test);
     }
 }
+
+#[test]
+fn detail_mixture_fugacity_low_pressure() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 1.0;
+    aga_test.density().unwrap();
+
+    let f = aga_test.mixture_fugacity();
+
+    assert!(f64::abs(f / aga_test.p - 1.0) < 1.0e-3);
+}
+
+#[test]
+fn detail_mixture_fugacity_matches_components() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+
+    let f = aga_test.mixture_fugacity();
+    let phi = aga_test.fugacity_coefficients();
+    let ln_phi_mix: f64 = aga_test
+        .x
+        .iter()
+        .zip(phi.iter())
+        .filter(|(x, _)| **x > 0.0)
+        .map(|(x, phi)| x * phi.ln())
+        .sum();
+
+    assert!(f64::abs(ln_phi_mix - (f / aga_test.p).ln()) < 1.0e-6);
+}
+
+#[test]
+fn detail_reused_instance_matches_fresh() {
+    let mut reused = Detail::new();
+    reused.set_composition(&COMP_FULL).unwrap();
+    reused.t = 250.0;
+    reused.p = 10_000.0;
+    reused.density().unwrap();
+    reused.properties();
+
+    reused.t = 400.0;
+    reused.p = 50_000.0;
+    reused.density().unwrap();
+    reused.properties();
+
+    let mut fresh = Detail::new();
+    fresh.set_composition(&COMP_FULL).unwrap();
+    fresh.t = 400.0;
+    fresh.p = 50_000.0;
+    fresh.density().unwrap();
+    fresh.properties();
+
+    assert_eq!(reused.d, fresh.d);
+    assert_eq!(reused.z, fresh.z);
+    assert_eq!(reused.h, fresh.h);
+    assert_eq!(reused.s, fresh.s);
+    assert_eq!(reused.cp, fresh.cp);
+    assert_eq!(reused.w, fresh.w);
+}
+
+#[test]
+fn detail_dp_dd_at_matches_finite_difference() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.d = 1.0;
+
+    let t = 400.0;
+    for d in [0.5, 5.0, 12.8] {
+        let h = 1.0e-4 * d;
+        let fd = (aga_test.pressure_at(t, d + h) - aga_test.pressure_at(t, d - h)) / (2.0 * h);
+        let dp_dd = aga_test.dp_dd_at(t, d);
+        assert!(f64::abs(dp_dd - fd) / dp_dd < 1.0e-6);
+    }
+
+    assert_eq!(aga_test.t, 300.0);
+    assert_eq!(aga_test.d, 1.0);
+}
+
+#[test]
+fn detail_pressure_at_keeps_properties() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let properties = aga_test.property_set();
+    let computed = aga_test.computed_fields();
+    let s_res = aga_test.residual_entropy();
+
+    aga_test.pressure_at(300.0, 5.0);
+    aga_test.dp_dd_at(300.0, 5.0);
+
+    assert_eq!(aga_test.property_set(), properties);
+    assert_eq!(aga_test.computed_fields(), computed);
+    assert_eq!(aga_test.residual_entropy(), s_res);
+}
+
+#[test]
+fn detail_set_composition_normalized() {
+    let comp = Composition {
+        methane: COMP_FULL.methane - 0.001,
+        ..COMP_FULL
+    };
+
+    let mut raw = Detail::new();
+    raw.set_composition(&comp).unwrap();
+    raw.t = 400.0;
+    raw.p = 50_000.0;
+    raw.density().unwrap();
+
+    let mut normalized = Detail::new();
+    normalized.set_composition_normalized(&comp).unwrap();
+    normalized.t = 400.0;
+    normalized.p = 50_000.0;
+    normalized.density().unwrap();
+
+    assert!(f64::abs(normalized.x.iter().sum::<f64>() - 1.0) < 1.0e-14);
+    assert!(f64::abs(raw.d - normalized.d) > 1.0e-4);
+    assert!(f64::abs(raw.d - normalized.d) / normalized.d < 1.0e-2);
+}
+
+#[test]
+fn detail_speed_of_sound_comparison() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    let (w, w0) = aga_test.speed_of_sound_comparison();
+    assert!(f64::abs(w - w0) / w0 > 0.1);
+
+    aga_test.p = 1.0;
+    aga_test.density().unwrap();
+    let (w, w0) = aga_test.speed_of_sound_comparison();
+    assert!(f64::abs(w - w0) / w0 < 1.0e-4);
+}
+
+#[test]
+fn detail_flow_consistency() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+
+    let rho = aga_test.mass_density();
+    let volume_flow = 2.5;
+    let mass_flow = rho * volume_flow;
+
+    assert!(f64::abs(Detail::density_from_flows(mass_flow, volume_flow) - rho) < 1.0e-10);
+    assert!(f64::abs(aga_test.flow_consistency(mass_flow, volume_flow)) < 1.0e-12);
+    assert!(f64::abs(aga_test.flow_consistency(1.05 * mass_flow, volume_flow) - 0.05) < 1.0e-12);
+}
+
+#[test]
+fn detail_property_map() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+
+    let map = aga_test.to_property_map();
+
+    assert_eq!(map.len(), 17);
+    assert_eq!(map["density"], aga_test.d);
+    assert_eq!(map["compressibility"], aga_test.z);
+    assert_eq!(map["speed_of_sound"], aga_test.w);
+}
+
+#[test]
+fn detail_binary_parameters() {
+    // Methane - carbon dioxide
+    let params = Detail::binary_parameters(0, 2);
+
+    assert_eq!(
+        params,
+        BinaryParams {
+            eij: 0.960_644,
+            uij: 0.963_827,
+            kij: 0.995_933,
+            gij: 0.807_653,
+        }
+    );
+    assert_eq!(Detail::binary_parameters(2, 0), params);
+    assert_eq!(Detail::binary_parameters(1, 1).eij, 1.0);
+}
+
+#[test]
+fn detail_set_binary_parameter() {
+    let mut standard = Detail::new();
+    standard.set_composition(&COMP_FULL).unwrap();
+    standard.t = 400.0;
+    standard.p = 50_000.0;
+    standard.density().unwrap();
+
+    let mut modified = Detail::new();
+    modified.set_composition(&COMP_FULL).unwrap();
+    modified.t = 400.0;
+    modified.p = 50_000.0;
+    modified.density().unwrap();
+
+    let kij = Detail::binary_parameters(0, 2).kij;
+    modified.set_binary_parameter(2, 0, BinaryParamKind::Kij, kij);
+    modified.density().unwrap();
+    assert_eq!(modified.z, standard.z);
+
+    modified.set_binary_parameter(0, 2, BinaryParamKind::Kij, 1.05 * kij);
+    modified.density().unwrap();
+    assert!(f64::abs(modified.z - standard.z) > 1.0e-3);
+
+    // New instances are not affected by the modified parameters
+    let mut fresh = Detail::new();
+    fresh.set_composition(&COMP_FULL).unwrap();
+    fresh.t = 400.0;
+    fresh.p = 50_000.0;
+    fresh.density().unwrap();
+    assert_eq!(fresh.z, standard.z);
+}
+
+#[cfg(feature = "extern")]
+#[test]
+fn detail_api_set_composition_checked() {
+    use aga8::{composition::CompositionError, ffi::detail::*};
+
+    unsafe {
+        let d_test = aga8_new();
+        let mut err: CompositionError = CompositionError::Ok;
+
+        let empty = [0.0; 21];
+        aga8_set_composition_checked(d_test, empty.as_ptr(), &mut err);
+        assert_eq!(err, CompositionError::Empty);
+
+        let valid = COMP_FULL.to_array();
+        aga8_set_composition_checked(d_test, valid.as_ptr(), &mut err);
+        assert_eq!(err, CompositionError::Ok);
+        assert!(f64::abs(aga8_calculate_molar_mass(d_test) - 20.543_330_51) < 1.0e-10);
+
+        aga8_free(d_test);
+    }
+}
+
+#[test]
+fn detail_apparent_molar_mass() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+
+    let mm_app = aga_test.apparent_molar_mass();
+    let mm = aga_test.molar_mass();
+    let rho_ideal = aga_test.p * mm_app / (8.31451 * aga_test.t);
+
+    assert!(f64::abs(mm_app - mm) > 1.0);
+    assert!(f64::abs(rho_ideal / aga_test.mass_density() - 1.0) < 1.0e-6);
+}
+
+#[test]
+fn detail_isobar() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 1_000.0;
+    aga_test.d = 0.4;
+
+    let isobar = aga_test.isobar(5_000.0, 250.0, 400.0, 16);
+
+    assert_eq!(isobar.len(), 16);
+    assert_eq!(isobar[0].0, 250.0);
+    assert!(f64::abs(isobar[15].0 - 400.0) < 1.0e-10);
+    for pair in isobar.windows(2) {
+        assert!(pair[1].1.h > pair[0].1.h);
+        assert!(f64::abs(pair[1].1.p - 5_000.0) < 1.0e-3);
+    }
+    assert_eq!(aga_test.t, 300.0);
+    assert_eq!(aga_test.p, 1_000.0);
+    assert_eq!(aga_test.d, 0.4);
+}
+
+#[test]
+fn detail_last_iterations() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+
+    assert!(aga_test.last_iterations > 0 && aga_test.last_iterations < 10);
+
+    aga_test.t = 90.0;
+    aga_test.p = 100.0;
+    aga_test.d = 0.0;
+
+    assert!(aga_test.density().is_err());
+    assert_eq!(aga_test.last_iterations, 20);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn detail_snapshot_json_round_trip() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+
+    let snapshot = aga_test.snapshot();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let restored: aga8::ResultSnapshot = serde_json::from_str(&json).unwrap();
+
+    assert!(f64::abs(restored.properties.d / aga_test.d - 1.0) < 1.0e-15);
+    assert_eq!(restored.composition, COMP_FULL);
+}
+
+#[test]
+fn detail_air_density() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+
+    let d_air = aga_test.air_density(288.15, 101.325).unwrap();
+    let rho_air = d_air * Composition::DRY_AIR.molar_mass();
+
+    assert!(f64::abs(rho_air - 1.225) < 1.0e-3);
+    assert_eq!(aga_test.x[0], COMP_FULL.methane);
+    assert_eq!(aga_test.d, d);
+    assert_eq!(aga_test.t, 400.0);
+}
+
+#[test]
+fn detail_dz_dt_p() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.p = 10_000.0;
+    let dt = 0.01;
+
+    aga_test.t = 300.0 + dt;
+    aga_test.density().unwrap();
+    let z_plus = aga_test.z;
+    aga_test.t = 300.0 - dt;
+    aga_test.density().unwrap();
+    let z_minus = aga_test.z;
+
+    aga_test.t = 300.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let numeric = (z_plus - z_minus) / (2.0 * dt);
+
+    assert!(f64::abs(aga_test.dz_dt_p() / numeric - 1.0) < 1.0e-4);
+}
+
+#[test]
+fn detail_standard_enthalpy() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+
+    let h = aga_test.standard_enthalpy(288.15, 101.325).unwrap();
+
+    assert!(h.is_finite());
+    assert_eq!(aga_test.t, 400.0);
+    assert_eq!(aga_test.p, 50_000.0);
+    assert_eq!(aga_test.d, d);
+}
+
+#[test]
+fn detail_dt_dp_isentropic() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 10_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let s0 = aga_test.s;
+    let dt_dp = aga_test.dt_dp_isentropic();
+
+    // Find the temperature with the same entropy at a slightly higher pressure
+    let dp = 10.0;
+    aga_test.p += dp;
+    for _ in 0..20 {
+        aga_test.density().unwrap();
+        aga_test.properties();
+        aga_test.t -= (aga_test.s - s0) * aga_test.t / aga_test.cp;
+    }
+    let numeric = (aga_test.t - 300.0) / dp;
+
+    assert!(f64::abs(dt_dp / numeric - 1.0) < 1.0e-3);
+}
+
+#[test]
+fn detail_set_composition_percent() {
+    let mut fraction = Detail::new();
+    fraction.set_composition(&COMP_FULL).unwrap();
+
+    let percent = Composition::from_array(&COMP_FULL.to_array().map(|x| x * 100.0));
+    let mut aga_test = Detail::new();
+    aga_test.set_composition_percent(&percent).unwrap();
+
+    for (a, b) in aga_test.x.iter().zip(fraction.x.iter()) {
+        assert!(f64::abs(a - b) < 1.0e-15);
+    }
+    assert_eq!(
+        aga_test.set_composition(&percent),
+        Err(aga8::composition::CompositionError::BadSum)
+    );
+}
+
+#[test]
+fn detail_solve_for_z() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+
+    aga_test.solve_for_z(300.0, 1.0).unwrap();
+    assert!(aga_test.d < 1.0e-6);
+    assert!(f64::abs(aga_test.z - 1.0) < 1.0e-10);
+
+    aga_test.solve_for_z(300.0, 0.99).unwrap();
+    let (d, p) = (aga_test.d, aga_test.p);
+    assert!(f64::abs(d / (p / (8.31451 * 300.0)) - 1.0 / 0.99) < 1.0e-10);
+
+    aga_test.d = 0.0;
+    aga_test.density().unwrap();
+    assert!(f64::abs(aga_test.d / d - 1.0) < 1.0e-6);
+    assert!(f64::abs(aga_test.z - 0.99) < 1.0e-6);
+}
+
+#[test]
+fn detail_ideal_helmholtz() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+
+    let a0 = aga_test.ideal_helmholtz();
+    assert!(a0.iter().all(|a| a.is_finite()));
+
+    // The residual part vanishes in the ideal gas limit
+    aga_test.p = 1.0e-3;
+    aga_test.d = 0.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let a0 = aga_test.ideal_helmholtz();
+
+    assert!(f64::abs(aga_test.s + a0[1]) < 1.0e-6);
+    assert!(f64::abs(aga_test.cv + a0[2]) < 1.0e-6);
+}
+
+#[test]
+fn detail_z_table() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    let pressures = [1_000.0, 2_000.0, 5_000.0, 10_000.0];
+
+    let table = aga_test.z_table(300.0, &pressures);
+
+    assert_eq!(table.len(), pressures.len());
+    let mut z_prev = 1.0;
+    for (z, p) in table.iter().zip(pressures.iter()) {
+        let z = *z.as_ref().unwrap();
+        let mut single = Detail::new();
+        single.set_composition(&COMP_FULL).unwrap();
+        single.t = 300.0;
+        single.p = *p;
+        single.density().unwrap();
+
+        assert!(f64::abs(z - single.z) < 1.0e-8);
+        assert!(z < z_prev);
+        z_prev = z;
+    }
+    assert_eq!(aga_test.t, 0.0);
+}
+
+#[test]
+fn detail_nonideality_factor() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+
+    let factor = aga_test.nonideality_factor().unwrap();
+
+    assert!(f64::abs(factor - (1.0 / aga_test.z - 1.0)) < 1.0e-6);
+    assert!(factor < 0.0);
+}
+
+#[test]
+fn detail_prepare_composition() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.prepare_composition();
+    aga_test.t = 400.0;
+    aga_test.d = 12.0;
+    let p1 = aga_test.pressure();
+
+    // A change below the cache tolerance uses the cached terms
+    aga_test.x[0] += 5.0e-8;
+    aga_test.x[1] -= 5.0e-8;
+    let p2 = aga_test.pressure();
+    assert_eq!(p2, p1);
+
+    aga_test.prepare_composition();
+    let p3 = aga_test.pressure();
+    assert_ne!(p3, p1);
+}
+
+#[test]
+fn detail_specific_properties() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+
+    let specific = aga_test.specific_properties();
+
+    assert!(f64::abs(specific.h / (aga_test.h / aga_test.mm * 1000.0) - 1.0) < 1.0e-12);
+    assert!(f64::abs(specific.cp / (aga_test.cp / aga_test.mm * 1000.0) - 1.0) < 1.0e-12);
+}
+
+#[test]
+fn detail_joule_thomson() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+
+    let jt = aga_test.joule_thomson().unwrap();
+    aga_test.properties();
+
+    assert!(f64::abs(jt / aga_test.jt - 1.0) < 1.0e-12);
+}
+
+#[test]
+fn detail_isochore() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+
+    let isochore = aga_test.isochore(5.0, 250.0, 400.0, 16);
+
+    assert_eq!(isochore.len(), 16);
+    for pair in isochore.windows(2) {
+        assert!(pair[1].1.p > pair[0].1.p);
+        assert_eq!(pair[1].1.d, 5.0);
+    }
+    assert_eq!(aga_test.d, 0.0);
+}
+
+#[test]
+fn detail_computed_fields() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    assert!(aga_test.computed_fields().is_empty());
+
+    aga_test.density().unwrap();
+    assert_eq!(aga_test.computed_fields(), ComputedFields::DENSITY);
+
+    aga_test.properties();
+    assert!(aga_test.computed_fields().contains(
+        ComputedFields::DENSITY | ComputedFields::PROPERTIES | ComputedFields::MOLAR_MASS
+    ));
+
+    // Methods that restore the state also restore the flags
+    let computed = aga_test.computed_fields();
+    aga_test.output_jacobian();
+    assert_eq!(aga_test.computed_fields(), computed);
+
+    aga_test.t = 300.0;
+    assert!(aga_test.computed_fields().is_empty());
+}
+
+#[test]
+fn detail_bulk_modulus() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+
+    let k_s = aga_test.bulk_modulus();
+    let rho = aga_test.mass_density();
+
+    assert!(k_s > 0.0);
+    assert!(f64::abs(f64::sqrt(k_s * 1000.0 / rho) / aga_test.w - 1.0) < 1.0e-12);
+    assert!(f64::abs(aga_test.acoustic_impedance() / (rho * aga_test.w) - 1.0) < 1.0e-12);
+    // K_s = kappa * p
+    assert!(f64::abs(k_s / (aga_test.kappa * aga_test.p) - 1.0) < 1.0e-6);
+}
+
+#[cfg(feature = "extern")]
+#[test]
+fn detail_api_individual_getters() {
+    use aga8::{composition::CompositionError, ffi::detail::*, DensityError};
+
+    unsafe {
+        let d_test = aga8_new();
+        let mut err: CompositionError = CompositionError::Ok;
+        aga8_set_composition(d_test, &COMP_FULL, &mut err);
+        aga8_set_pressure(d_test, 50_000.0);
+        aga8_set_temperature(d_test, 400.0);
+        let mut dens_err: DensityError = DensityError::Ok;
+        aga8_calculate_density(d_test, &mut dens_err);
+        aga8_calculate_properties(d_test);
+
+        let results = aga8_get_properties(d_test);
+        assert_eq!(aga8_get_z(d_test), results.z);
+        assert_eq!(aga8_get_speed_of_sound(d_test), results.w);
+        assert_eq!(aga8_get_cp(d_test), results.cp);
+        assert_eq!(aga8_get_isentropic_exponent(d_test), results.kappa);
+
+        aga8_free(d_test);
+    }
+}
+
+#[test]
+fn detail_reference_z_points() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+
+    let points = aga_test.reference_z_points();
+
+    assert_eq!(points.len(), 4);
+    for (_t, _p, z) in points {
+        assert!(z > 0.9 && z < 1.1);
+    }
+}
+
+#[test]
+fn detail_density_uncertainty() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+
+    let mut sigma = [0.0; 21];
+    assert_eq!(aga_test.density_uncertainty(&sigma), Ok(0.0));
+
+    sigma[0] = 0.001;
+    sigma[2] = 0.000_5;
+    let small = aga_test.density_uncertainty(&sigma).unwrap();
+    sigma[0] = 0.002;
+    let large = aga_test.density_uncertainty(&sigma).unwrap();
+
+    assert!(small > 0.0);
+    assert!(large > small);
+    assert_eq!(aga_test.x[0], COMP_FULL.methane);
+    assert_eq!(aga_test.t, 400.0);
+}
+
+#[test]
+fn detail_spinodal_densities() {
+    let mut aga_test = Detail::new();
+    aga_test
+        .set_composition(&Composition {
+            methane: 1.0,
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert_eq!(aga_test.spinodal_densities(300.0), None);
+
+    let (d_vapor, d_liquid) = aga_test.spinodal_densities(150.0).unwrap();
+    assert!(d_vapor > 0.0 && d_vapor < d_liquid);
+    assert!(f64::abs(aga_test.dp_dd_at(150.0, d_vapor)) < 1.0e-6);
+    assert!(f64::abs(aga_test.dp_dd_at(150.0, d_liquid)) < 1.0e-6);
+    assert!(aga_test.dp_dd_at(150.0, 0.5 * (d_vapor + d_liquid)) < 0.0);
+
+    // d(P)/d(D) is already negative at the first grid point of 0.05 mol/l
+    aga_test
+        .set_composition(&Composition {
+            methane: 0.7,
+            propane: 0.3,
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(aga_test.dp_dd_at(110.0, 0.05) < 0.0);
+    let (d_vapor, _) = aga_test.spinodal_densities(110.0).unwrap();
+    assert!(d_vapor > 0.0 && d_vapor < 0.05);
+    assert!(f64::abs(aga_test.dp_dd_at(110.0, d_vapor)) < 1.0e-6);
+}
+
+#[test]
+fn detail_critical_flow_function() {
+    let mut aga_test = Detail::new();
+
+    aga_test.kappa = 1.3;
+    assert!(f64::abs(aga_test.critical_flow_function() - 0.667_26) < 1.0e-5);
+
+    aga_test.kappa = 1.0;
+    let limit = aga_test.critical_flow_function();
+    aga_test.kappa = 1.001;
+    let near = aga_test.critical_flow_function();
+    assert!(f64::abs(limit - f64::exp(-0.5)) < 1.0e-12);
+    assert!(f64::abs(near - limit) < 1.0e-3);
+}
+
+#[test]
+fn detail_density_vs_temperature() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    let temperatures = [260.0, 280.0, 300.0, 320.0, 340.0];
+
+    let densities = aga_test.density_vs_temperature(5_000.0, &temperatures);
+
+    assert_eq!(densities.len(), temperatures.len());
+    for pair in densities.windows(2) {
+        assert!(pair[1].as_ref().unwrap() < pair[0].as_ref().unwrap());
+    }
+    assert_eq!(aga_test.p, 0.0);
+}
+
+#[test]
+fn detail_properties_checked() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    let before = aga_test.property_set();
+
+    assert_eq!(
+        aga_test.properties_checked(),
+        Err(aga8::PropertyError::DensityNotSolved)
+    );
+    assert_eq!(aga_test.property_set(), before);
+
+    aga_test.density().unwrap();
+    let props = aga_test.properties_checked().unwrap();
+    assert!(f64::abs(props.d - 12.807_924_036_488_01) < 1.0e-10);
+}
+
+#[test]
+fn detail_residual_entropy() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+
+    let s_res = aga_test.residual_entropy();
+    let s_ideal = -aga_test.ideal_helmholtz()[1];
+
+    assert!(s_res < 0.0);
+    assert!(f64::abs(s_ideal + s_res - aga_test.s) < 1.0e-10);
+
+    // Methods that overwrite the Helmholtz energy terms do not change the stored value
+    let d = aga_test.d;
+    aga_test.pressure_at(400.0, d);
+    aga_test.mixture_fugacity();
+    aga_test.fugacity_coefficients();
+    assert_eq!(aga_test.residual_entropy(), s_res);
+}
+
+#[test]
+fn detail_density_refined() {
+    let mut aga_test = Detail::new();
+    aga_test
+        .set_composition(&Composition {
+            methane: 0.9,
+            ethane: 0.1,
+            ..Default::default()
+        })
+        .unwrap();
+    aga_test.t = 210.0;
+    aga_test.p = 9_100.0;
+
+    aga_test.density().unwrap();
+    let residual = f64::abs(aga_test.pressure() - aga_test.p) / aga_test.p;
+    aga_test.d = 0.0;
+    aga_test.density_refined(1.0e-12).unwrap();
+    let residual_refined = f64::abs(aga_test.pressure() - aga_test.p) / aga_test.p;
+
+    assert!(residual_refined < residual);
+    assert!(residual_refined < 1.0e-14);
+}
+
+#[test]
+fn detail_infer_molar_mass() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 10_000.0;
+    aga_test.density().unwrap();
+    let measured = aga_test.mass_density();
+
+    let mm = aga_test
+        .infer_molar_mass(300.0, 10_000.0, measured)
+        .unwrap();
+
+    assert!(f64::abs(mm / aga_test.molar_mass() - 1.0) < 1.0e-6);
+}
+
+#[test]
+fn detail_metering_summary() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+
+    let summary = aga_test.metering_summary(300.0, 5_000.0).unwrap();
+
+    let mut line = Detail::new();
+    line.set_composition(&COMP_FULL).unwrap();
+    line.t = 300.0;
+    line.p = 5_000.0;
+    line.density().unwrap();
+
+    assert_eq!(summary.line.d, line.d);
+    assert_eq!(summary.standard.fpv, 1.0);
+    assert!(summary.line.fpv > 1.0);
+    assert!(summary.normal.d > summary.standard.d);
+    assert!(summary.relative_density > 0.6 && summary.relative_density < 0.8);
+    assert_eq!(aga_test.t, 0.0);
+}
+
+#[test]
+fn detail_output_jacobian() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+
+    let jacobian = aga_test.output_jacobian();
+
+    assert!(jacobian.dd_dp > 0.0);
+    assert!(f64::abs(jacobian.dd_dp * aga_test.dp_dd - 1.0) < 1.0e-4);
+    assert!(
+        f64::abs(
+            jacobian.dd_dt + aga_test.dp_dt / aga_test.dp_dd * jacobian.dd_dp * aga_test.dp_dd
+        ) < 1.0e-4
+    );
+    assert!(f64::abs(jacobian.dh_dt / aga_test.cp - 1.0) < 1.0e-4);
+    assert_eq!(aga_test.t, 400.0);
+}
+
+#[test]
+fn detail_z_at_density() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let (d, z) = (aga_test.d, aga_test.z);
+    aga_test.t = 300.0;
+    let properties = aga_test.property_set();
+    let computed = aga_test.computed_fields();
+
+    assert!(f64::abs(aga_test.z_at_density(400.0, d) - z) < 1.0e-12);
+    assert_eq!(aga_test.t, 300.0);
+    assert_eq!(aga_test.z, z);
+    assert_eq!(aga_test.property_set(), properties);
+    assert_eq!(aga_test.computed_fields(), computed);
+}
+
+#[test]
+fn detail_energy_flow() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+
+    let e = aga_test.energy_flow(1000.0, 288.15, 101.325).unwrap();
+    let e2 = aga_test.energy_flow(2000.0, 288.15, 101.325).unwrap();
+
+    assert!(e > 0.0);
+    assert!(f64::abs(e2 / e - 2.0) < 1.0e-12);
+    assert_eq!(aga_test.t, 400.0);
+    assert_eq!(aga_test.p, 50_000.0);
+}
+
+#[test]
+fn detail_set_molar_volume() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.d = 1.0 / 0.25;
+    let p = aga_test.pressure();
+
+    aga_test.d = 0.0;
+    aga_test.set_molar_volume(0.25);
+
+    assert_eq!(aga_test.d, 4.0);
+    assert_eq!(aga_test.pressure(), p);
+
+    aga_test.set_molar_volume(0.0);
+    assert_eq!(aga_test.d, 4.0);
+}
+
+#[test]
+fn detail_temperature_from_pd() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.d = 12.0;
+    let p = aga_test.pressure();
+
+    let t = aga_test
+        .temperature_from_pd(p, 12.0, (250.0, 600.0))
+        .unwrap();
+
+    assert!(f64::abs(t - 400.0) < 1.0e-6);
+    assert!(f64::abs(aga_test.p / p - 1.0) < 1.0e-8);
+
+    let t = aga_test
+        .temperature_from_pd(p, 12.0, (600.0, 250.0))
+        .unwrap();
+    assert!(f64::abs(t - 400.0) < 1.0e-6);
+
+    let properties = aga_test.property_set();
+    assert_eq!(
+        aga_test.temperature_from_pd(p, 12.0, (450.0, 600.0)),
+        Err(aga8::DensityError::IterationFail)
+    );
+    assert_eq!(aga_test.property_set(), properties);
+}
+
+#[test]
+fn detail_vcf_table() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+
+    let table = aga_test.vcf_table(
+        &[288.15, 300.0, 320.0],
+        &[101.325, 2_000.0, 5_000.0, 10_000.0],
+        288.15,
+        101.325,
+    );
+
+    assert_eq!(table.len(), 3);
+    assert!(table.iter().all(|row| row.len() == 4));
+    assert!(f64::abs(table[0][0] - 1.0) < 1.0e-12);
+    assert!(table[0][3] > table[0][2] && table[0][3] > table[2][3]);
+    assert_eq!(aga_test.d, d);
+}
+
+#[test]
+fn detail_z_uncertainty_estimate() {
+    let mut aga_test = Detail::new();
+    aga_test
+        .set_composition(&Composition {
+            methane: 0.95,
+            nitrogen: 0.01,
+            carbon_dioxide: 0.01,
+            ethane: 0.025,
+            propane: 0.005,
+            ..Default::default()
+        })
+        .unwrap();
+    aga_test.t = 288.15;
+    aga_test.p = 5_000.0;
+
+    assert_eq!(aga_test.z_uncertainty_estimate(), 0.001);
+
+    aga_test.p = 50_000.0;
+    assert_eq!(aga_test.z_uncertainty_estimate(), 0.005);
+
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 288.15;
+    aga_test.p = 5_000.0;
+    assert_eq!(aga_test.z_uncertainty_estimate(), 0.003);
+
+    aga_test.t = 800.0;
+    assert!(aga_test.z_uncertainty_estimate().is_infinite());
+}
+
+#[test]
+fn detail_density_virial() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+
+    aga_test.p = 101.325;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+    aga_test.density_virial().unwrap();
+    assert!(f64::abs(aga_test.d / d - 1.0) < 1.0e-4);
+
+    aga_test.p = 1_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+    aga_test.density_virial().unwrap();
+    assert!(f64::abs(aga_test.d / d - 1.0) < 1.0e-3);
+
+    // Not valid at high pressure
+    aga_test.p = 5_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+    aga_test.density_virial().unwrap();
+    assert!(f64::abs(aga_test.d / d - 1.0) > 1.0e-2);
+
+    aga_test.p = 20_000.0;
+    assert_eq!(
+        aga_test.density_virial(),
+        Err(aga8::DensityError::IterationFail)
+    );
+}
+
+#[test]
+fn detail_db_dt() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    let db_dt = aga_test.db_dt();
+
+    let h = 0.01;
+    aga_test.t = 300.0 + h;
+    let b_plus = aga_test.second_virial();
+    aga_test.t = 300.0 - h;
+    let b_minus = aga_test.second_virial();
+
+    assert!(db_dt > 0.0);
+    assert!(f64::abs((b_plus - b_minus) / (2.0 * h) / db_dt - 1.0) < 1.0e-6);
+}
+
+#[test]
+fn detail_convergence_basin() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+
+    let guesses = [0.0, 1.0, 5.0, 10.0, 15.0, 20.0];
+    let basin = aga_test.convergence_basin(400.0, 50_000.0, &guesses);
+
+    assert_eq!(basin.len(), guesses.len());
+    for (guess, result) in basin {
+        let root = result.unwrap();
+        assert!(f64::abs(root / d - 1.0) < 1.0e-6, "guess {guess}");
+    }
+    assert_eq!(aga_test.d, d);
+}
+
+#[test]
+fn detail_mean_cp() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 5_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let cp = aga_test.cp;
+
+    let mean = aga_test.mean_cp(5_000.0, 300.0, 300.0, 10).unwrap();
+    assert!(f64::abs(mean / cp - 1.0) < 1.0e-10);
+
+    let mean = aga_test.mean_cp(5_000.0, 300.0, 400.0, 20).unwrap();
+    assert!(mean.is_finite() && mean != cp);
+    assert_eq!(aga_test.t, 300.0);
+    assert_eq!(aga_test.cp, cp);
+}
+
+#[test]
+fn detail_enthalpy_change_ideal_error() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+
+    let low = aga_test
+        .enthalpy_change_ideal_error(300.0, 10.0, 350.0, 20.0)
+        .unwrap();
+    let high = aga_test
+        .enthalpy_change_ideal_error(300.0, 10_000.0, 350.0, 20_000.0)
+        .unwrap();
+
+    assert!(low.abs() < 2.0);
+    assert!(high.abs() > 100.0);
+}
+
+#[test]
+fn detail_dw_dt() {
+    let mut aga_test = Detail::new();
+    aga_test
+        .set_composition(&Composition {
+            methane: 1.0,
+            ..Default::default()
+        })
+        .unwrap();
+    aga_test.p = 100.0;
+    let mut w_at = |t: f64| {
+        aga_test.t = t;
+        aga_test.density().unwrap();
+        aga_test.properties();
+        aga_test.w
+    };
+    let estimate = (w_at(301.0) - w_at(299.0)) / 2.0;
+    w_at(300.0);
+
+    let dw_dt = aga_test.dw_dt();
+
+    assert!(dw_dt > 0.0);
+    assert!(f64::abs(dw_dt / estimate - 1.0) < 1.0e-3);
+    assert_eq!(dw_dt, aga_test.output_jacobian().dw_dt);
+    assert_eq!(aga_test.t, 300.0);
+}
+
+#[test]
+fn detail_z_vs_temperature() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    let temperatures = [270.0, 280.0, 290.0, 300.0, 310.0, 320.0];
+
+    let z: Vec<f64> = aga_test
+        .z_vs_temperature(7_000.0, &temperatures)
+        .into_iter()
+        .map(|z| z.unwrap())
+        .collect();
+
+    assert!(z.windows(2).all(|w| w[1] > w[0]));
+    for (&t, z) in temperatures.iter().zip(z) {
+        aga_test.t = t;
+        aga_test.p = 7_000.0;
+        aga_test.d = 0.0;
+        aga_test.density().unwrap();
+        assert!(f64::abs(aga_test.z / z - 1.0) < 1.0e-7);
+    }
+}
+
+#[test]
+fn detail_binary_z_contributions() {
+    let mut aga_test = Detail::new();
+    aga_test
+        .set_composition(&Composition {
+            methane: 0.5,
+            nitrogen: 0.2,
+            carbon_dioxide: 0.3,
+            ..Default::default()
+        })
+        .unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 5_000.0;
+    aga_test.density().unwrap();
+
+    let contributions = aga_test.binary_z_contributions();
+
+    assert_eq!(contributions.len(), 3);
+    assert_eq!(contributions[0].0, Component::Methane);
+    assert_eq!(contributions[0].1, Component::CarbonDioxide);
+}
+
+#[test]
+fn detail_gibbs_duhem_residual() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    let (x, d) = (aga_test.x, aga_test.d);
+
+    let residual = aga_test.gibbs_duhem_residual();
+
+    assert!(residual < 1.0e-3);
+    assert_eq!(aga_test.x, x);
+    assert_eq!(aga_test.d, d);
+}
+
+#[test]
+fn detail_ph_diagram_isotherm() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+
+    let isotherm = aga_test.ph_diagram_isotherm(300.0, 100.0, 10_000.0, 25);
+
+    assert_eq!(isotherm.len(), 25);
+    assert_eq!(isotherm[0].1, 100.0);
+    assert_eq!(isotherm[24].1, 10_000.0);
+    assert!(isotherm.windows(2).all(|w| w[1].0 < w[0].0));
+}
+
+#[test]
+fn detail_sonic_nozzle_mass_flow() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 5_000.0;
+
+    let q1 = aga_test.sonic_nozzle_mass_flow(1.0e-4).unwrap();
+    let q2 = aga_test.sonic_nozzle_mass_flow(2.0e-4).unwrap();
+
+    // About 1 kg/s through a 1 cm² throat at 50 bar
+    assert!(q1 > 0.5 && q1 < 2.0);
+    assert!(f64::abs(q2 / q1 - 2.0) < 1.0e-12);
+}
+
+#[test]
+fn detail_contaminant_sensitivity() {
+    let mut aga_test = Detail::new();
+    aga_test
+        .set_composition(&Composition {
+            methane: 0.95,
+            ethane: 0.05,
+            ..Default::default()
+        })
+        .unwrap();
+    let x = aga_test.x;
+
+    let none = aga_test
+        .contaminant_sensitivity(Component::CarbonDioxide, 0.0, 300.0, 5_000.0)
+        .unwrap();
+    let co2 = aga_test
+        .contaminant_sensitivity(Component::CarbonDioxide, 0.01, 300.0, 5_000.0)
+        .unwrap();
+
+    assert_eq!(none, 0.0);
+    assert!(co2 > 0.0);
+    assert_eq!(aga_test.x, x);
+}
+
+#[test]
+fn detail_isothermal_jt() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 10_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let mu_t = aga_test.isothermal_jt();
+
+    let dp = 1.0;
+    let mut h = [0.0; 2];
+    for (i, p) in [10_000.0 - dp, 10_000.0 + dp].iter().enumerate() {
+        aga_test.p = *p;
+        aga_test.density().unwrap();
+        aga_test.properties();
+        h[i] = aga_test.h;
+    }
+    let numeric = (h[1] - h[0]) / (2.0 * dp);
+
+    assert!(mu_t < 0.0);
+    assert!(f64::abs(mu_t / numeric - 1.0) < 1.0e-5);
+}
+
+#[test]
+fn detail_ideal_curve() {
+    let mut aga_test = Detail::new();
+    aga_test
+        .set_composition(&Composition {
+            methane: 0.95,
+            ethane: 0.05,
+            ..Default::default()
+        })
+        .unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 5_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+
+    let curve = aga_test.ideal_curve(&[250.0, 300.0, 350.0, 1_000.0]);
+
+    for (t, p) in &curve[..3] {
+        let p = p.unwrap();
+        assert!(p > 0.0);
+        let mut check = Detail::new();
+        check.x = aga_test.x;
+        check.t = *t;
+        check.p = p;
+        check.density().unwrap();
+        assert!(f64::abs(check.z - 1.0) < 1.0e-8);
+    }
+    // Above the Boyle temperature Z is larger than 1.0 at all densities
+    assert_eq!(curve[3], (1_000.0, None));
+    assert_eq!(aga_test.d, d);
+}
+
+#[test]
+fn detail_density_measurement_uncertainty() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 300.0;
+    aga_test.p = 10_000.0;
+    aga_test.density().unwrap();
+    let d = aga_test.d;
+
+    let zero = aga_test.density_measurement_uncertainty(0.0, 0.0).unwrap();
+    let u1 = aga_test.density_measurement_uncertainty(0.0, 5.0).unwrap();
+    let u2 = aga_test.density_measurement_uncertainty(0.0, 10.0).unwrap();
+    let u_t = aga_test.density_measurement_uncertainty(0.1, 10.0).unwrap();
+
+    assert_eq!(zero, 0.0);
+    assert!(u1 > 0.0);
+    assert!(f64::abs(u2 / u1 - 2.0) < 1.0e-12);
+    assert!(u_t > u2);
+    assert_eq!(aga_test.d, d);
+}
+
+#[test]
+fn detail_pressure_residual() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let (d, p) = (aga_test.d, aga_test.p);
+    let s_res = aga_test.residual_entropy();
+
+    let solved = aga_test.pressure_residual(400.0, 50_000.0, d);
+    let wrong = aga_test.pressure_residual(400.0, 50_000.0, 1.1 * d);
+
+    assert!(solved.abs() < 1.0e-7);
+    assert!(wrong > 0.1);
+    assert_eq!(aga_test.d, d);
+    assert_eq!(aga_test.p, p);
+    assert_eq!(aga_test.residual_entropy(), s_res);
+}
+
+#[test]
+fn detail_set_mass_density() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    let (d, z) = (aga_test.d, aga_test.z);
+    let rho = aga_test.mass_density();
+
+    aga_test.d = 0.0;
+    aga_test.p = 0.0;
+    aga_test.set_mass_density(rho);
+    aga_test.properties();
+
+    assert!(f64::abs(aga_test.d / d - 1.0) < 1.0e-14);
+    assert!(f64::abs(aga_test.p / 50_000.0 - 1.0) < 1.0e-9);
+    assert!(f64::abs(aga_test.z - z) < 1.0e-12);
+}
+
+#[test]
+fn detail_compressibility() {
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_FULL).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+
+    let z = aga_test.compressibility().unwrap();
+    aga_test.properties();
+
+    assert!(f64::abs(z - aga_test.z) < 1.0e-14);
+    assert!(f64::abs(z - 1.173_801_364_147_326) < 1.0e-10);
+}
@@ -1,5 +1,6 @@
-use aga8::composition::Composition;
-use aga8::gerg2008::Gerg2008;
+use aga8::composition::{Component, Composition};
+use aga8::gerg2008::{DensityFailure, Gerg2008, PairModel, PhaseLabel, PhaseProximityWarning};
+use aga8::DensityError;
 use rand::prelude::*;
 
 const COMP_FULL: Composition = Composition {
@@ -201,3 +202,406 @@ fn gerg2008_normal_range() {
         let _ = gerg_test.density(0);
     }
 }
+
+#[test]
+fn gerg_property_map() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+    gerg_test.density(0).unwrap();
+    gerg_test.properties();
+
+    let map = gerg_test.to_property_map();
+
+    assert_eq!(map.len(), 17);
+    assert_eq!(map["density"], gerg_test.d);
+    assert_eq!(map["isentropic_exponent"], gerg_test.kappa);
+}
+
+#[test]
+fn gerg_isenthalpic_expansion() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_PARTIAL).unwrap();
+    gerg_test.t = 300.0;
+    gerg_test.p = 10_000.0;
+    gerg_test.density(0).unwrap();
+    gerg_test.properties();
+    let h_in = gerg_test.h;
+
+    gerg_test.isenthalpic_to_pressure(5_000.0).unwrap();
+
+    let dt = gerg_test.t - 300.0;
+    assert_eq!(gerg_test.p, 5_000.0);
+    assert!(f64::abs(gerg_test.h - h_in) < 1.0e-5);
+    assert!(dt < -10.0 && dt > -40.0);
+
+    // Hydrogen has a negative Joule-Thomson coefficient, and is heated by the expansion
+    gerg_test
+        .set_composition(&Composition {
+            hydrogen: 1.0,
+            ..Default::default()
+        })
+        .unwrap();
+    gerg_test.t = 300.0;
+    gerg_test.p = 10_000.0;
+    gerg_test.density(0).unwrap();
+    gerg_test.properties();
+    let h_in = gerg_test.h;
+    assert!(gerg_test.jt < 0.0);
+
+    gerg_test.isenthalpic_to_pressure(5_000.0).unwrap();
+
+    let dt = gerg_test.t - 300.0;
+    assert!(f64::abs(gerg_test.h - h_in) < 1.0e-5);
+    assert!(dt > 0.0 && dt < 5.0);
+}
+
+#[test]
+fn gerg_reduced_state() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+
+    let (tr, _pr) = gerg_test.reduced_state();
+    gerg_test.t /= tr;
+    let (tr, pr) = gerg_test.reduced_state();
+    let zc = gerg_test.critical_compressibility();
+
+    assert!(f64::abs(tr - 1.0) < 1.0e-12);
+    assert!(pr > 0.0);
+    assert!(zc > 0.2 && zc < 0.4);
+
+    gerg_test.t = 400.0;
+    gerg_test.density(0).unwrap();
+    gerg_test.properties();
+    let properties = gerg_test.property_set();
+    gerg_test.reduced_state();
+    assert_eq!(gerg_test.property_set(), properties);
+}
+
+#[test]
+fn gerg_last_iterations() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+    gerg_test.density(0).unwrap();
+
+    assert!(gerg_test.last_iterations > 0 && gerg_test.last_iterations < 10);
+
+    gerg_test.t = 90.0;
+    gerg_test.p = 100.0;
+    gerg_test.d = 0.0;
+
+    assert!(gerg_test.density(0).is_err());
+    assert_eq!(gerg_test.last_iterations, 50);
+}
+
+#[test]
+fn gerg_phase_gibbs_energies() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test
+        .set_composition(&Composition {
+            propane: 1.0,
+            ..Default::default()
+        })
+        .unwrap();
+    // Compressed liquid propane, above the vapor pressure of about 1 MPa
+    gerg_test.t = 300.0;
+    gerg_test.p = 1_200.0;
+
+    let phases = gerg_test.phase_gibbs_energies();
+
+    assert_eq!(phases.len(), 2);
+    let (vapor, liquid) = (phases[0], phases[1]);
+    assert!(vapor.0 < 1.0 && liquid.0 > 10.0);
+    assert!(liquid.1 < vapor.1);
+    assert_eq!(gerg_test.t, 300.0);
+    assert_eq!(gerg_test.p, 1_200.0);
+}
+
+#[test]
+fn gerg_dew_point_temperature() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test
+        .set_composition(&Composition {
+            methane: 0.7,
+            ethane: 0.1,
+            propane: 0.1,
+            n_butane: 0.05,
+            n_pentane: 0.05,
+            ..Default::default()
+        })
+        .unwrap();
+
+    let t_dew = gerg_test
+        .dew_point_temperature(2_000.0, (200.0, 300.0))
+        .unwrap();
+
+    assert!(t_dew > 230.0 && t_dew < 240.0);
+    assert_eq!(
+        gerg_test.dew_point_temperature(2_000.0, (250.0, 300.0)),
+        Err(DensityError::IterationFail)
+    );
+}
+
+#[test]
+fn gerg_phase_label() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+
+    let mut label = |t: f64, p: f64| {
+        gerg_test.t = t;
+        gerg_test.p = p;
+        gerg_test.d = 0.0;
+        gerg_test.density(0).unwrap();
+        gerg_test.phase_label()
+    };
+
+    assert_eq!(label(300.0, 1_000.0), PhaseLabel::Gas);
+    assert_eq!(label(400.0, 5_000.0), PhaseLabel::Supercritical);
+    assert_eq!(label(400.0, 50_000.0), PhaseLabel::DensePhase);
+    assert_eq!(label(150.0, 5_000.0), PhaseLabel::Liquid);
+}
+
+#[test]
+fn gerg_pressure_from_mass_density() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+    gerg_test.density(0).unwrap();
+    gerg_test.properties();
+    let mass_density = gerg_test.d * gerg_test.mm;
+    let properties = gerg_test.property_set();
+
+    let p = gerg_test.pressure_from_mass_density(400.0, mass_density);
+    gerg_test.pressure_from_mass_density(300.0, 0.5 * mass_density);
+
+    assert!(f64::abs(p / 50_000.0 - 1.0) < 1.0e-6);
+    assert_eq!(gerg_test.property_set(), properties);
+}
+
+#[test]
+fn gerg_hydrogen_blend_support() {
+    let comp = Composition {
+        methane: 0.7,
+        nitrogen: 0.03,
+        ethane: 0.05,
+        hydrogen: 0.2,
+        oxygen: 0.02,
+        ..Default::default()
+    };
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&comp).unwrap();
+
+    let support = gerg_test.hydrogen_blend_support();
+
+    assert_eq!(support.hydrogen, 0.2);
+    assert_eq!(support.pairs.len(), 4);
+    assert_eq!(
+        support.pair(Component::Methane),
+        Some(PairModel::DepartureFunction)
+    );
+    assert_eq!(
+        support.pair(Component::Nitrogen),
+        Some(PairModel::AdjustedReducing)
+    );
+    assert_eq!(
+        support.pair(Component::Ethane),
+        Some(PairModel::AdjustedReducing)
+    );
+    assert_eq!(
+        support.pair(Component::Oxygen),
+        Some(PairModel::CombiningRules)
+    );
+    assert_eq!(support.pair(Component::Propane), None);
+}
+
+#[test]
+fn gerg_composition_sweep_matches_fresh() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+
+    for i in 0..10 {
+        let comp = Composition {
+            methane: 0.78 - 0.01 * i as f64,
+            propane: 0.03 + 0.01 * i as f64,
+            ..COMP_FULL
+        };
+        gerg_test.set_composition(&comp).unwrap();
+        gerg_test.d = 0.0;
+        gerg_test.density(0).unwrap();
+        gerg_test.properties();
+
+        let mut fresh: Gerg2008 = Gerg2008::new();
+        fresh.set_composition(&comp).unwrap();
+        fresh.t = 400.0;
+        fresh.p = 50_000.0;
+        fresh.density(0).unwrap();
+        fresh.properties();
+
+        assert_eq!(gerg_test.d, fresh.d);
+        assert_eq!(gerg_test.cp, fresh.cp);
+        assert_eq!(gerg_test.w, fresh.w);
+    }
+}
+
+#[test]
+fn gerg_compress_isentropic_ratio() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_PARTIAL).unwrap();
+    gerg_test.t = 300.0;
+    gerg_test.p = 5_000.0;
+    gerg_test.density(0).unwrap();
+    gerg_test.properties();
+    let s_in = gerg_test.s;
+
+    assert_eq!(gerg_test.compress_isentropic_ratio(1.0).unwrap(), 0.0);
+
+    let work = gerg_test.compress_isentropic_ratio(2.0).unwrap();
+
+    assert!(work > 0.0);
+    assert_eq!(gerg_test.p, 10_000.0);
+    assert!(gerg_test.t > 300.0);
+    assert!(f64::abs(gerg_test.s - s_in) < 1.0e-7);
+}
+
+#[test]
+fn gerg_density_failure_record() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test
+        .set_composition(&Composition {
+            propane: 1.0,
+            ..Default::default()
+        })
+        .unwrap();
+    gerg_test.t = 250.0;
+    gerg_test.p = 1_000.0;
+
+    assert_eq!(gerg_test.density(0), Err(DensityError::IterationFail));
+
+    let failure: DensityFailure = gerg_test.last_failure.unwrap();
+    assert!(failure.negative_dp_dd);
+    assert!(failure.vlog_min < failure.vlog_max);
+
+    gerg_test.t = 400.0;
+    gerg_test.d = 0.0;
+    gerg_test.density(0).unwrap();
+    assert_eq!(gerg_test.last_failure, None);
+}
+
+#[test]
+fn gerg_normal_boiling_point() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+    let x = gerg_test.x;
+
+    let t = gerg_test.normal_boiling_point(Component::Propane).unwrap();
+
+    // 231.04 K
+    assert!(f64::abs(t - 231.04) < 0.5);
+    assert_eq!(gerg_test.x, x);
+    assert_eq!(gerg_test.t, 400.0);
+}
+
+#[test]
+fn gerg_heat_capacity_with_warning() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 400.0;
+    gerg_test.p = 5_000.0;
+    gerg_test.density(0).unwrap();
+
+    let (cp, warning) = gerg_test.heat_capacity_with_warning();
+    assert!(cp > 0.0);
+    assert_eq!(warning, None);
+
+    // Close to the critical point of propane
+    gerg_test
+        .set_composition(&Composition {
+            propane: 1.0,
+            ..Default::default()
+        })
+        .unwrap();
+    gerg_test.t = 370.0;
+    gerg_test.d = 5.0;
+
+    let (cp_near, warning) = gerg_test.heat_capacity_with_warning();
+    assert!(cp_near > 10.0 * cp);
+    assert!(matches!(
+        warning,
+        Some(PhaseProximityWarning::NearPhaseBoundary(_))
+    ));
+}
+
+#[test]
+fn gerg_density_robust() {
+    // A state from the range of gerg2008_normal_range where density() fails
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 120.0;
+    gerg_test.p = 1_000.0;
+    gerg_test.d = 0.0;
+    assert_eq!(gerg_test.density(0), Err(DensityError::IterationFail));
+
+    gerg_test.density_robust().unwrap();
+    let (d, h, iterations) = (gerg_test.d, gerg_test.h, gerg_test.last_iterations);
+
+    // Compressed liquid
+    assert!(d > 20.0);
+    assert!(f64::abs(gerg_test.pressure() / 1_000.0 - 1.0) < 1.0e-8);
+    assert!(iterations > 0);
+    // The properties are for the chosen root
+    gerg_test.properties();
+    assert_eq!(gerg_test.h, h);
+
+    // Same density as density() where it converges
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+    gerg_test.d = 0.0;
+    gerg_test.density(0).unwrap();
+    let d = gerg_test.d;
+    gerg_test.density_robust().unwrap();
+
+    assert!(f64::abs(gerg_test.d / d - 1.0) < 1.0e-8);
+}
+
+#[test]
+fn gerg_reset_cache() {
+    let mut gerg_test: Gerg2008 = Gerg2008::new();
+    gerg_test.set_composition(&COMP_FULL).unwrap();
+    gerg_test.t = 400.0;
+    gerg_test.p = 50_000.0;
+    gerg_test.density(0).unwrap();
+    let d_old = gerg_test.d;
+
+    // A change below the cache threshold uses the old reducing parameters
+    gerg_test.x[1] -= 5.0e-8;
+    gerg_test.x[2] += 5.0e-8;
+    gerg_test.d = 0.0;
+    gerg_test.density(0).unwrap();
+    let d_cached = gerg_test.d;
+
+    gerg_test.reset_cache();
+    gerg_test.d = 0.0;
+    gerg_test.density(0).unwrap();
+    gerg_test.properties();
+
+    let mut fresh: Gerg2008 = Gerg2008::new();
+    fresh.x = gerg_test.x;
+    fresh.t = 400.0;
+    fresh.p = 50_000.0;
+    fresh.density(0).unwrap();
+    fresh.properties();
+
+    assert_ne!(d_cached, fresh.d);
+    assert_ne!(gerg_test.d, d_old);
+    assert_eq!(gerg_test.d, fresh.d);
+    assert_eq!(gerg_test.cp, fresh.cp);
+}
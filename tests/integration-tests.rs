@@ -1,5 +1,42 @@
 use aga8::detail::Detail;
 
+// The composition used in the demo example
+const COMP_DEMO: aga8::composition::Composition = aga8::composition::Composition {
+    methane: 0.778_24,
+    nitrogen: 0.02,
+    carbon_dioxide: 0.06,
+    ethane: 0.08,
+    propane: 0.03,
+    isobutane: 0.001_5,
+    n_butane: 0.003,
+    isopentane: 0.000_5,
+    n_pentane: 0.001_65,
+    hexane: 0.002_15,
+    heptane: 0.000_88,
+    octane: 0.000_24,
+    nonane: 0.000_15,
+    decane: 0.000_09,
+    hydrogen: 0.004,
+    oxygen: 0.005,
+    carbon_monoxide: 0.002,
+    water: 0.000_1,
+    hydrogen_sulfide: 0.002_5,
+    helium: 0.007,
+    argon: 0.001,
+};
+
+// Properties of 90 % methane and 10 % ethane at 300 K and 5 MPa, calculated with DETAIL
+fn methane_ethane_properties() -> aga8::PropertySet {
+    let mut aga_test = Detail::new();
+    aga_test.x[0] = 0.9;
+    aga_test.x[3] = 0.1;
+    aga_test.t = 300.0;
+    aga_test.p = 5_000.0;
+    aga_test.density().unwrap();
+    aga_test.properties();
+    aga_test.property_set()
+}
+
 #[test]
 fn test_gas_2() {
     let mut aga8_test: Detail = Detail::new();
@@ -62,3 +99,281 @@ fn test_gas_2() {
     assert!(f64::abs(aga8_test.cp - 126.207) < 0.000_1);
     assert!(f64::abs(aga8_test.w - 266.393_3) < 0.000_1);
 }
+
+#[test]
+fn solve_with_fallback_reports_model() {
+    use aga8::composition::Composition;
+    use aga8::{solve_with_fallback, DensityError, Model};
+
+    let natural_gas = Composition {
+        methane: 0.965,
+        nitrogen: 0.003,
+        carbon_dioxide: 0.006,
+        ethane: 0.018,
+        propane: 0.004_5,
+        isobutane: 0.001,
+        n_butane: 0.001,
+        isopentane: 0.000_5,
+        n_pentane: 0.000_3,
+        hexane: 0.000_7,
+        ..Default::default()
+    };
+    let (props, model) = solve_with_fallback(&natural_gas, 291.15, 14_601.325).unwrap();
+    assert_eq!(model, Model::Gerg);
+    assert!(f64::abs(props.d - 7.730_483_295_277_388) < 1.0e-10);
+
+    // GERG2008 fails to iterate for liquid propane at 90 K
+    let propane = Composition {
+        propane: 1.0,
+        ..Default::default()
+    };
+    let (props, model) = solve_with_fallback(&propane, 90.0, 100.0).unwrap();
+    assert_eq!(model, Model::Detail);
+    assert_eq!(props.t, 90.0);
+
+    let unchecked = Composition {
+        methane: 0.5,
+        ..Default::default()
+    };
+    assert_eq!(
+        solve_with_fallback(&unchecked, 300.0, 5_000.0),
+        Err(DensityError::IterationFail)
+    );
+}
+
+#[test]
+fn compare_models_demo_composition() {
+    use aga8::composition::Composition;
+    use aga8::{compare_models, DensityError};
+
+    let comp = COMP_DEMO;
+    let cmp = compare_models(&comp, 400.0, 50_000.0).unwrap();
+
+    assert!(cmp.d.abs() < 1.0e-3);
+    for diff in [cmp.z, cmp.cp, cmp.cv, cmp.w] {
+        assert!(diff.is_finite());
+        assert!(diff != 0.0);
+        assert!(diff.abs() < 0.05);
+    }
+
+    let unchecked = Composition {
+        methane: -0.1,
+        ..COMP_DEMO
+    };
+    assert_eq!(
+        compare_models(&unchecked, 400.0, 50_000.0),
+        Err(DensityError::IterationFail)
+    );
+}
+
+#[test]
+fn blend_sweep_hydrogen() {
+    use aga8::composition::{Component, Composition};
+    use aga8::{blend_sweep, DensityError};
+
+    let natural_gas = Composition {
+        methane: 0.965,
+        nitrogen: 0.003,
+        carbon_dioxide: 0.006,
+        ethane: 0.018,
+        propane: 0.004_5,
+        isobutane: 0.001,
+        n_butane: 0.001,
+        isopentane: 0.000_5,
+        n_pentane: 0.000_3,
+        hexane: 0.000_7,
+        ..Default::default()
+    };
+    let fractions = [0.0, 0.05, 0.1, 0.15, 0.2];
+
+    let sweep = blend_sweep(
+        &natural_gas,
+        Component::Hydrogen,
+        &fractions,
+        300.0,
+        5_000.0,
+    );
+
+    assert_eq!(sweep.len(), fractions.len());
+    for pair in sweep.windows(2) {
+        let (a, b) = (pair[0].as_ref().unwrap(), pair[1].as_ref().unwrap());
+        assert!(b.z > a.z);
+        assert!(b.w > a.w);
+    }
+
+    let sweep = blend_sweep(&natural_gas, Component::Hydrogen, &[1.5], 300.0, 5_000.0);
+    assert_eq!(sweep, vec![Err(DensityError::IterationFail)]);
+}
+
+#[test]
+fn sos_model_difference_demo_composition() {
+    use aga8::composition::Composition;
+    use aga8::{sos_model_difference, DensityError};
+
+    let comp = COMP_DEMO;
+
+    let dw = sos_model_difference(&comp, 400.0, 50_000.0).unwrap();
+
+    assert!(dw != 0.0);
+    assert!(dw.abs() < 0.01);
+    assert!(sos_model_difference(&comp, 90.0, 100.0).is_err());
+
+    let percent = Composition::from_array(&comp.to_array().map(|x| 100.0 * x));
+    assert_eq!(
+        sos_model_difference(&percent, 400.0, 50_000.0),
+        Err(DensityError::IterationFail)
+    );
+}
+
+#[test]
+fn best_density_match_gerg() {
+    use aga8::composition::Composition;
+    use aga8::gerg2008::Gerg2008;
+    use aga8::{best_density_match, DensityError, Model};
+
+    let comp = Composition {
+        methane: 0.9,
+        ethane: 0.06,
+        carbon_dioxide: 0.04,
+        ..Default::default()
+    };
+    let mut gerg = Gerg2008::new();
+    gerg.set_composition(&comp).unwrap();
+    gerg.t = 300.0;
+    gerg.p = 10_000.0;
+    gerg.density(0).unwrap();
+
+    let (model, error) = best_density_match(&comp, 300.0, 10_000.0, gerg.d).unwrap();
+
+    assert_eq!(model, Model::Gerg);
+    assert!(error.abs() < 1.0e-12);
+
+    let empty = Composition::default();
+    assert_eq!(
+        best_density_match(&empty, 300.0, 10_000.0, gerg.d),
+        Err(DensityError::IterationFail)
+    );
+}
+
+#[test]
+fn property_set_bytes_round_trip() {
+    use aga8::PropertySet;
+
+    let properties = methane_ethane_properties();
+
+    let bytes = properties.to_bytes();
+
+    assert_eq!(bytes.len(), PropertySet::BYTES);
+    assert_eq!(PropertySet::from_bytes(&bytes), properties);
+}
+
+#[test]
+fn solve_from_mass_fractions_demo_composition() {
+    use aga8::composition::Composition;
+    use aga8::detail::Detail;
+    use aga8::solve_from_mass_fractions;
+
+    let mut aga_test = Detail::new();
+    aga_test.set_composition(&COMP_DEMO).unwrap();
+    aga_test.t = 400.0;
+    aga_test.p = 50_000.0;
+    aga_test.density().unwrap();
+
+    let mm = COMP_DEMO.molar_mass();
+    let x = COMP_DEMO.to_array();
+    let mut w = [0.0; 21];
+    for (i, wi) in w.iter_mut().enumerate() {
+        let mut pure = [0.0; 21];
+        pure[i] = 1.0;
+        *wi = x[i] * Composition::from_array(&pure).molar_mass() / mm;
+    }
+
+    let properties = solve_from_mass_fractions(w, 400.0, 50_000.0).unwrap();
+
+    assert!(f64::abs(properties.d / aga_test.d - 1.0) < 1.0e-10);
+    assert!(solve_from_mass_fractions([0.0; 21], 400.0, 50_000.0).is_err());
+}
+
+#[test]
+fn composition_from_wobbe_rd_round_trip() {
+    use aga8::composition::{Component, Composition, CompositionError};
+    use aga8::composition_from_wobbe_rd;
+
+    let comp = Composition {
+        methane: 0.9,
+        nitrogen: 0.03,
+        carbon_dioxide: 0.01,
+        ethane: 0.04,
+        propane: 0.02,
+        ..Default::default()
+    };
+    let base = Composition {
+        methane: 0.95,
+        nitrogen: 0.0,
+        ..comp
+    };
+
+    let result = composition_from_wobbe_rd(
+        comp.wobbe_index(),
+        comp.ideal_relative_density(),
+        &base,
+        Component::Nitrogen,
+    )
+    .unwrap();
+
+    assert!(result.distance(&comp) < 1.0e-10);
+    assert_eq!(
+        composition_from_wobbe_rd(60.0, 0.6, &base, Component::Methane),
+        Err(CompositionError::OutOfRange)
+    );
+}
+
+#[test]
+fn property_set_approx_eq() {
+    use aga8::PropertySet;
+
+    let properties = methane_ethane_properties();
+    let perturbed = PropertySet {
+        w: properties.w * 1.001,
+        ..properties
+    };
+
+    assert!(properties.approx_eq(&properties, 0.0));
+    assert!(!properties.approx_eq(&perturbed, 1.0e-4));
+    assert!(properties.approx_eq(&perturbed, 1.0e-2));
+
+    let diff = properties.diff(&perturbed);
+
+    assert!(f64::abs(diff.w - properties.w * 0.001) < 1.0e-10);
+    assert_eq!(PropertySet { w: 0.0, ..diff }, PropertySet::default());
+}
+
+#[test]
+fn composition_from_wobbe_rd_methane_base() {
+    use aga8::composition::{Component, Composition};
+    use aga8::composition_from_wobbe_rd;
+
+    let base = Composition {
+        methane: 1.0,
+        ..Default::default()
+    };
+
+    for i in 1..100 {
+        let nitrogen = 0.001 * i as f64;
+        let comp = Composition {
+            methane: 1.0 - nitrogen,
+            nitrogen,
+            ..Default::default()
+        };
+
+        let result = composition_from_wobbe_rd(
+            comp.wobbe_index(),
+            comp.ideal_relative_density(),
+            &base,
+            Component::Nitrogen,
+        )
+        .unwrap();
+
+        assert!(result.distance(&comp) < 1.0e-10);
+    }
+}